@@ -0,0 +1,124 @@
+//! Compares the flat `Matrix` layout to the previous nested one on full scans and flood fills
+//! of a 1000x1000 grid with a maze-like pattern. Run with `cargo bench --bench matrix_layout`.
+#![feature(test)]
+
+extern crate test;
+
+use std::hint::black_box;
+use test::Bencher;
+use aoc_2025::utils::matrix::Matrix;
+
+const SIZE: usize = 1000;
+
+/// The previous `Matrix` layout with one `Vec` per row, kept as a reference for the benchmark.
+struct NestedMatrix<T> {
+    width: usize,
+    height: usize,
+    data: Vec<Vec<T>>,
+}
+
+impl<T> NestedMatrix<T> {
+    fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.data.get(y).and_then(|row| row.get(x))
+    }
+}
+
+/// Common access used by the benchmarked algorithms, so both layouts run identical code.
+trait BenchGrid {
+    fn size(&self) -> (usize, usize);
+    fn cell(&self, x: usize, y: usize) -> Option<&u8>;
+}
+
+impl BenchGrid for NestedMatrix<u8> {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Option<&u8> {
+        self.get(x, y)
+    }
+}
+
+impl BenchGrid for Matrix<u8> {
+    fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Option<&u8> {
+        self.get(x, y)
+    }
+}
+
+/// The same maze-like pattern in both layouts.
+fn grids(width: usize, height: usize) -> (Matrix<u8>, NestedMatrix<u8>) {
+    let flat = Matrix::new_fill(width, height, 0u8).map_xy(|_, x, y| if x % 7 == 3 && y % 11 != 0 { 1 } else { 0 });
+    let nested = NestedMatrix { width, height, data: (0..height).map(|y| flat.row(y).to_vec()).collect() };
+    (flat, nested)
+}
+
+fn full_scan(grid: &impl BenchGrid) -> usize {
+    let (width, height) = grid.size();
+    let mut sum = 0;
+    for y in 0..height {
+        for x in 0..width {
+            sum += *grid.cell(x, y).unwrap() as usize;
+        }
+    }
+    sum
+}
+
+fn flood_fill(grid: &impl BenchGrid) -> usize {
+    let (width, height) = grid.size();
+    let mut visited = vec![false; width * height];
+    let mut stack = vec![(0, 0)];
+    let value = *grid.cell(0, 0).unwrap();
+    let mut count = 0;
+
+    while let Some((x, y)) = stack.pop() {
+        if visited[y * width + x] || *grid.cell(x, y).unwrap() != value {
+            continue;
+        }
+        visited[y * width + x] = true;
+        count += 1;
+
+        if x > 0 { stack.push((x - 1, y)); }
+        if y > 0 { stack.push((x, y - 1)); }
+        if x < width - 1 { stack.push((x + 1, y)); }
+        if y < height - 1 { stack.push((x, y + 1)); }
+    }
+    count
+}
+
+#[bench]
+fn full_scan_nested(bencher: &mut Bencher) {
+    let (_, nested) = grids(SIZE, SIZE);
+    bencher.iter(|| full_scan(black_box(&nested)));
+}
+
+#[bench]
+fn full_scan_flat(bencher: &mut Bencher) {
+    let (flat, _) = grids(SIZE, SIZE);
+    bencher.iter(|| full_scan(black_box(&flat)));
+}
+
+#[bench]
+fn flood_fill_nested(bencher: &mut Bencher) {
+    let (_, nested) = grids(SIZE, SIZE);
+    bencher.iter(|| flood_fill(black_box(&nested)));
+}
+
+#[bench]
+fn flood_fill_flat(bencher: &mut Bencher) {
+    let (flat, _) = grids(SIZE, SIZE);
+    bencher.iter(|| flood_fill(black_box(&flat)));
+}
+
+#[test]
+fn layouts_give_the_same_results() {
+    let (flat, nested) = grids(20, 30);
+    assert_eq!(full_scan(&flat), 3 * 27);
+    assert_eq!(full_scan(&nested), full_scan(&flat));
+    assert_eq!(flood_fill(&flat), 20 * 30 - 3 * 27);
+    assert_eq!(flood_fill(&nested), flood_fill(&flat));
+    assert_eq!(nested.get(20, 0), None);
+}
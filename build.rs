@@ -0,0 +1,16 @@
+use std::process::Command;
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or("rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .unwrap_or("unknown rustc".to_string());
+
+    println!("cargo:rustc-env=AOC_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=AOC_BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or("unknown".to_string()));
+    println!("cargo:rerun-if-changed=build.rs");
+}
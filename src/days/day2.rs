@@ -1,11 +1,16 @@
 use std::fmt::Display;
 use rayon::prelude::*;
 use crate::utils::solution::{solution, Solution};
+use crate::utils::test_set::mark_part1_finished;
 
 #[derive(Default)]
 pub struct ProductCodes;
 
 impl Solution for ProductCodes {
+    fn title(&self) -> &str {
+        "Gift Shop"
+    }
+
     fn solve(&self, input: String) -> (Box<dyn Display>, Box<dyn Display>) {
         let ranges = input
             .split(',')
@@ -18,44 +23,23 @@ impl Solution for ProductCodes {
             })
             .collect::<Vec<_>>();
 
-        let mut sum_1: u64 = 0;
-        let mut sum_2: u64 = 0;
-        for range in ranges {
-            let sums = (range.start..=range.end).into_par_iter().map(|i| {
-                let mut sum_1: u64 = 0;
-                let mut sum_2: u64 = 0;
-
+        let sum_1 = ranges.iter()
+            .map(|range| (range.start..=range.end).into_par_iter().filter(|i| {
                 let id = i.to_string();
                 let mid = id.len() / 2;
-                let start = &id[..mid];
-                let end = &id[mid..];
-                if start == end {
-                    sum_1 = i;
-                }
-
-                let mut has_valid = false;
-                for len in 1..=id.len() / 2 {
-                    if id.len() % len != 0 {
-                        continue;
-                    }
+                id[..mid] == id[mid..]
+            }).sum::<u64>())
+            .sum::<u64>();
+        mark_part1_finished();
 
-                    let seg0 = &id[..len];
-                    let reassembled = seg0.repeat(id.len() / len);
-                    if reassembled == id {
-                        has_valid = true;
-                    }
-                }
-
-                if has_valid {
-                    sum_2 = i;
-                }
-
-                (sum_1, sum_2)
-            });
-
-            sum_1 += sums.clone().map(|(sum_1, _)| sum_1).sum::<u64>();
-            sum_2 += sums.map(|(_, sum_2)| sum_2).sum::<u64>();
-        }
+        let sum_2 = ranges.iter()
+            .map(|range| (range.start..=range.end).into_par_iter().filter(|i| {
+                let id = i.to_string();
+                (1..=id.len() / 2)
+                    .filter(|len| id.len() % len == 0)
+                    .any(|len| id[..len].repeat(id.len() / len) == id)
+            }).sum::<u64>())
+            .sum::<u64>();
 
         solution!(sum_1, sum_2)
     }
@@ -0,0 +1,4 @@
+#![feature(str_split_whitespace_remainder)]
+
+pub mod utils;
+pub mod days;
@@ -1,18 +1,71 @@
-#![feature(str_split_whitespace_remainder)]
+use aoc_2025::days::day1::SecretSafe;
+use aoc_2025::days::day2::ProductCodes;
+use aoc_2025::utils::aoc::AdventOfCode;
+#[cfg(feature = "alloc-stats")]
+use aoc_2025::utils::alloc_counter::CountingAllocator;
+use aoc_2025::utils::solution::SolveTest;
+use aoc_2025::utils::visualize;
+use aoc_2025::utils::visualize::VisualizeMode;
 
-use crate::days::day1::SecretSafe;
-use crate::days::day2::ProductCodes;
-use crate::utils::aoc::AdventOfCode;
-use crate::utils::solution::SolveTest;
-
-mod utils;
-mod days;
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
     let mut aoc = AdventOfCode::default();
-    aoc.add_solution(1, Box::new(SecretSafe::default()));
-    aoc.add_solution(2, Box::new(ProductCodes::default()));
+    aoc.add_solution(2025, 1, Box::new(SecretSafe::default()));
+    aoc.add_solution(2025, 2, Box::new(ProductCodes::default()));
+
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Some(index) = args.iter().position(|arg| arg == "--year") {
+        let year = args.get(index + 1).and_then(|year| year.parse().ok()).expect("Invalid year");
+        aoc.select_year(year);
+        args.drain(index..=index + 1);
+    }
+    if let Some(index) = args.iter().position(|arg| arg == "--dry-run") {
+        aoc.set_report_dry_run(true);
+        args.remove(index);
+    }
+    if let Some(index) = args.iter().position(|arg| arg == "--force") {
+        aoc.set_report_force(true);
+        args.remove(index);
+    }
+    let mut fps = 30;
+    if let Some(index) = args.iter().position(|arg| arg == "--fps") {
+        fps = args.get(index + 1).and_then(|fps| fps.parse().ok()).expect("Invalid fps");
+        args.drain(index..=index + 1);
+    }
+    let mut scale = 4;
+    if let Some(index) = args.iter().position(|arg| arg == "--scale") {
+        scale = args.get(index + 1).and_then(|scale| scale.parse().ok()).expect("Invalid scale");
+        args.drain(index..=index + 1);
+    }
+    if let Some(index) = args.iter().position(|arg| arg == "--visualize") {
+        visualize::enable(VisualizeMode::Terminal, fps, scale);
+        args.remove(index);
+    }
+    if let Some(index) = args.iter().position(|arg| arg == "--record") {
+        let folder = args.get(index + 1).expect("Missing recording folder").into();
+        visualize::enable(VisualizeMode::Record(folder), fps, scale);
+        args.drain(index..=index + 1);
+    }
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["solve", day] => aoc.solve_day(day.parse().expect("Invalid day"), SolveTest::Last),
+        ["bench", day] => aoc.bench_day(day.parse().expect("Invalid day"), SolveTest::Last),
+        ["all"] => aoc.solve_all(),
+        ["history", "export", path] => aoc.export_history(path),
+        ["history", day] => aoc.print_history(day.parse().expect("Invalid day")),
+        ["submit", day, part] => {
+            let day = day.parse().expect("Invalid day");
+            let part = part.parse().expect("Invalid part");
+            aoc.submit_day(day, part);
+        }
+        _ => {
+            aoc.solve_day(2, SolveTest::Last);
+            aoc.solve_all();
+        }
+    }
 
-    aoc.solve_day(2, SolveTest::Last);
-    aoc.solve_all();
+    visualize::finish();
 }
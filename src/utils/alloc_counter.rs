@@ -0,0 +1,156 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// A global allocator that forwards to the system allocator while counting allocations.
+/// It is only installed when the `alloc-stats` feature is enabled, see `main.rs`.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        LIVE_BYTES.fetch_sub(layout.size(), Relaxed);
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            LIVE_BYTES.fetch_sub(layout.size(), Relaxed);
+            record_allocation(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn record_allocation(size: usize) {
+    ALLOCATIONS.fetch_add(1, Relaxed);
+    BYTES_ALLOCATED.fetch_add(size, Relaxed);
+    let live = LIVE_BYTES.fetch_add(size, Relaxed) + size;
+    PEAK_LIVE_BYTES.fetch_max(live, Relaxed);
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes_allocated: usize,
+    /// The highest number of bytes that were live at once, on top of what was live before the measurement.
+    pub peak_live_bytes: usize,
+}
+
+impl AllocStats {
+    pub fn format(&self) -> String {
+        format!("{} allocations, {} allocated, {} peak", self.allocations, format_bytes(self.bytes_allocated), format_bytes(self.peak_live_bytes))
+    }
+}
+
+/// Counts the allocations made while running `f`, or returns `None` when the counting
+/// allocator isn't installed.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    if !cfg!(feature = "alloc-stats") {
+        return (f(), None);
+    }
+
+    let allocations = ALLOCATIONS.load(Relaxed);
+    let bytes_allocated = BYTES_ALLOCATED.load(Relaxed);
+    let live_bytes = LIVE_BYTES.load(Relaxed);
+    PEAK_LIVE_BYTES.store(live_bytes, Relaxed);
+
+    let result = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Relaxed) - allocations,
+        bytes_allocated: BYTES_ALLOCATED.load(Relaxed) - bytes_allocated,
+        peak_live_bytes: PEAK_LIVE_BYTES.load(Relaxed).saturating_sub(live_bytes),
+    };
+
+    (result, Some(stats))
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc-stats")]
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_bytes(3 << 40), "3072.0 GiB");
+    }
+
+    #[test]
+    fn formats_stats() {
+        let stats = AllocStats { allocations: 3, bytes_allocated: 2048, peak_live_bytes: 100 };
+        assert_eq!(stats.format(), "3 allocations, 2.0 KiB allocated, 100 B peak");
+    }
+
+    #[test]
+    #[cfg(not(feature = "alloc-stats"))]
+    fn measure_without_the_allocator() {
+        assert_eq!(measure(|| vec![1u8; 100].len()), (100, None));
+    }
+
+    /// Other tests allocate and free on their own threads at the same time, so only lower bounds
+    /// of the counters that never decrease hold.
+    #[test]
+    #[cfg(feature = "alloc-stats")]
+    fn measure_counts_allocations() {
+        let (sum, stats) = measure(|| {
+            let first = vec![1u8; 4096];
+            let mut second = Vec::<u64>::with_capacity(16);
+            second.extend([1, 2, 3]);
+            let sum = first.len() as u64 + second.iter().sum::<u64>();
+            drop(first);
+            sum
+        });
+        let stats = stats.unwrap();
+
+        assert_eq!(sum, 4102);
+        assert!(stats.allocations >= 2);
+        assert!(stats.bytes_allocated >= 4096 + 16 * 8);
+    }
+}
@@ -1,81 +1,172 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering::Relaxed;
 use std::time::Duration;
-use chrono::Datelike;
 use colored::Colorize;
 use terminal_size::Width;
+use crate::utils::alloc_counter::{format_bytes, AllocStats};
+use crate::utils::badge::ReportStyle;
+use crate::utils::report::{longest_duration, DayReport, ReportConfig, ReportData};
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::history::{current_commit, machine_id, TimingHistory, TimingRecord};
+use crate::utils::calendar::{format_countdown, Clock, EventCalendar, SystemClock};
 use crate::utils::solution::{Solution, SolveTest};
+use crate::utils::submit::submit_answer;
 use crate::utils::test_set::{TestRunResult, PRINT_RESULTS};
 
-#[derive(Default)]
 pub struct AdventOfCode {
-    solutions: HashMap<u8, Box<dyn Solution>>,
+    solutions: HashMap<(i32, u8), Box<dyn Solution>>,
+    calendar: EventCalendar,
+    clock: Box<dyn Clock>,
+    report: ReportConfig,
+}
+
+impl Default for AdventOfCode {
+    fn default() -> Self {
+        Self::new(EventCalendar::default())
+    }
 }
 
 impl AdventOfCode {
-    pub fn add_solution(&mut self, day: u8, solution: Box<dyn Solution>) {
-        if let Some(_) = self.solutions.insert(day, solution) {
-            panic!("A solution has already been added for day {day}!")
+    pub fn new(calendar: EventCalendar) -> Self {
+        Self {
+            solutions: HashMap::new(),
+            calendar,
+            clock: Box::new(SystemClock),
+            report: ReportConfig::new(calendar.year()),
         }
     }
 
-    #[allow(dead_code)]
-    pub fn solve_day(&self, day: u8, solve_test: SolveTest) {
-        if let Some(solution) = self.solutions.get(&day) {
-            solution.solve_test(day, solve_test);
-        } else {
-            panic!("No solution exists for day {day}!");
+    pub fn set_report_style(&mut self, report_style: ReportStyle) {
+        self.report.style = report_style;
+    }
+
+    /// Adds a file containing results blocks. When none are added, the README is used.
+    pub fn add_report_file(&mut self, path: impl Into<PathBuf>) {
+        self.report.files.push(path.into());
+    }
+
+    /// Replaces timings in the report files even if they were measured on a different machine.
+    pub fn set_report_force(&mut self, force: bool) {
+        self.report.force = force;
+    }
+
+    /// Prints the changes to the report files instead of writing them.
+    pub fn set_report_dry_run(&mut self, dry_run: bool) {
+        self.report.dry_run = dry_run;
+    }
+
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Selects the event year used by all subsequent commands.
+    pub fn select_year(&mut self, year: i32) {
+        self.calendar = EventCalendar::for_year(year);
+        self.report.default_year = year;
+    }
+
+    pub fn add_solution(&mut self, year: i32, day: u8, solution: Box<dyn Solution>) {
+        if let Some(_) = self.solutions.insert((year, day), solution) {
+            panic!("A solution has already been added for day {day} of {year}!")
         }
     }
 
-    #[allow(dead_code)]
-    pub fn bench_day(&self, day: u8, solve_test: SolveTest) {
-        if let Some(solution) = self.solutions.get(&day) {
-            PRINT_RESULTS.store(false, Relaxed);
+    fn get_solution(&self, day: u8) -> &dyn Solution {
+        match self.solutions.get(&(self.calendar.year(), day)) {
+            Some(solution) => solution.as_ref(),
+            None => panic!("No solution exists for day {day} of {}!", self.calendar.year()),
+        }
+    }
 
-            let iterations = 100;
-            let mut total_time = 0;
+    pub fn solve_day(&self, day: u8, solve_test: SolveTest) {
+        self.get_solution(day).solve_test(self.calendar.year(), day, solve_test);
+    }
 
-            for _ in 0..iterations {
-                match solution.solve_test(day, solve_test) {
-                    Some(result) => {
-                        total_time += result.elapsed.as_micros();
-                    }
-                    None => {
-                        println!("{}", "The program did not return a solution, aborting benchmark!".red().bold());
-                        return;
+    pub fn bench_day(&self, day: u8, solve_test: SolveTest) {
+        let solution = self.get_solution(day);
+        PRINT_RESULTS.store(false, Relaxed);
+
+        let iterations = 100;
+        let mut total_time = 0;
+        let mut total_allocs: Option<AllocStats> = None;
+
+        for _ in 0..iterations {
+            match solution.solve_test(self.calendar.year(), day, solve_test) {
+                Some(result) => {
+                    total_time += result.elapsed.as_micros();
+                    if let Some(stats) = result.alloc_stats {
+                        let total = total_allocs.get_or_insert_default();
+                        total.allocations += stats.allocations;
+                        total.bytes_allocated += stats.bytes_allocated;
+                        total.peak_live_bytes = total.peak_live_bytes.max(stats.peak_live_bytes);
                     }
                 }
+                None => {
+                    println!("{}", "The program did not return a solution, aborting benchmark!".red().bold());
+                    return;
+                }
             }
+        }
 
-            let duration = Duration::from_micros((total_time / iterations) as u64);
-            println!("Average execution time: {}", format_elapsed(duration, true));
+        let duration = Duration::from_micros((total_time / iterations) as u64);
+        println!("Average execution time: {}", format_elapsed(duration, true));
+        if let Some(total) = total_allocs {
+            println!("Average allocations: {} ({} allocated, {} peak)", total.allocations / iterations as usize,
+                     format_bytes(total.bytes_allocated / iterations as usize), format_bytes(total.peak_live_bytes));
+        }
+        println!("{} {}", "Measured on".bold(), Fingerprint::current().summary().italic());
 
-            PRINT_RESULTS.store(true, Relaxed);
-        } else {
-            panic!("No solution exists for day {day}!");
+        PRINT_RESULTS.store(true, Relaxed);
+    }
+
+    pub fn submit_day(&self, day: u8, part: u8) {
+        if part != 1 && part != 2 {
+            panic!("Invalid part {part}, expected 1 or 2!");
+        }
+
+        if day == 0 || day > self.calendar.last_day() {
+            panic!("Day {day} is not part of the {} event!", self.calendar.year());
         }
+
+        let now = self.clock.now();
+        if !self.calendar.is_unlocked(day, now) {
+            let remaining = self.calendar.release_time(day) - now;
+            println!("{}", format!("Day {day} unlocks in {}, nothing to submit yet!", format_countdown(remaining)).red().bold());
+            return;
+        }
+
+        let year = self.calendar.year();
+        let Some(result) = self.get_solution(day).solve_test(year, day, SolveTest::Last) else {
+            println!("{}", "The program did not return a solution, nothing to submit!".red().bold());
+            return;
+        };
+
+        let answer = if part == 1 { result.part1_result } else { result.part2_result };
+        println!();
+        submit_answer(year, day, part, &answer);
     }
 
-    #[allow(dead_code)]
     pub fn solve_all(&self) {
         let prev_print_results = PRINT_RESULTS.load(Relaxed);
         PRINT_RESULTS.store(false, Relaxed);
-        let mut keys = self.solutions.keys().collect::<Vec<&u8>>();
+        let year = self.calendar.year();
+        let mut keys = self.days().collect::<Vec<u8>>();
         keys.sort();
 
         let mut results: HashMap<u8, Option<TestRunResult>> = HashMap::new();
 
         for day in &keys {
-            let solution = self.solutions.get(day).unwrap();
-            println!("Running solution for day {day}...");
-            let result = solution.solve_test(**day, SolveTest::Last);
-            results.insert(**day, result);
+            let solution = self.get_solution(*day);
+            println!("Running solution for day {day} of {year}...");
+            let result = solution.solve_test(year, *day, SolveTest::Last);
+            results.insert(*day, result);
         }
 
         println!();
 
-        let longest_duration = Self::get_longest_duration(&results);
+        let longest_duration = longest_duration(results.values());
 
         println!("All solutions have been executed, here are the results:");
         for day in &keys {
@@ -96,148 +187,74 @@ impl AdventOfCode {
                 None => "",
                 Some(result) => &format_progress_bar(&result.elapsed, &longest_duration, true),
             };
-            println!("   Day {: >2}: {} {}  {}", format!("{}", day).purple().bold(), status_label, progress_label, duration_label);
+            let alloc_label = match result.as_ref().and_then(|result| result.alloc_stats) {
+                None => String::new(),
+                Some(stats) => format!("  ({})", stats.format()).white().to_string(),
+            };
+            println!("   Day {: >2}: {} {}  {}{}", format!("{}", day).purple().bold(), status_label, progress_label, duration_label, alloc_label);
         }
+        println!("{} {}", "Measured on".bold(), self.report.fingerprint.summary().italic());
         PRINT_RESULTS.store(prev_print_results, Relaxed);
 
         self.check_date_and_print_link();
-        Self::write_progress_report(results);
-    }
-
-    fn check_date_and_print_link(&self) {
-        let local_date = chrono::Utc::now() - chrono::Duration::hours(7);
-        if local_date.month() != 12 {
-            return;
-        }
-
-        let mut keys = self.solutions.keys().collect::<Vec<&u8>>();
-        keys.sort();
-
-        let last_day_with_solution = **keys.last().unwrap() as u32;
-
-        if local_date.day() > last_day_with_solution && last_day_with_solution < 25 {
-            let next_day = last_day_with_solution + 1;
-            let problem_url = format!("https://adventofcode.com/{}/day/{}", local_date.year(), next_day);
-            println!();
-            println!("{}: {}", "Your next AoC problem is ready! Grab it here".purple().bold().italic(), problem_url);
+        if !cfg!(debug_assertions) {
+            Self::append_history(year, &keys, &results);
         }
+        self.report.write(&ReportData {
+            year,
+            days: keys.iter()
+                .map(|day| DayReport { day: *day, title: self.get_solution(*day).title(), result: results.get(day).unwrap() })
+                .collect(),
+        });
     }
 
-    fn write_progress_report(results: HashMap<u8, Option<TestRunResult>>) {
-        if cfg!(debug_assertions) {
-            return;
+    fn append_history(year: i32, days: &[u8], results: &HashMap<u8, Option<TestRunResult>>) {
+        let commit = current_commit();
+        let machine = machine_id();
+        let fingerprint = Fingerprint::current();
+        let records = days.iter()
+            .filter_map(|day| results.get(day).unwrap().as_ref().map(|result| (day, result)))
+            .map(|(day, result)| TimingRecord::new(year, *day, result, commit.clone(), &machine, &fingerprint))
+            .collect::<Vec<_>>();
+
+        if let Err(error) = TimingHistory::for_year(year).append(&records) {
+            println!("{} {}", "Failed to update the timing history:".red().bold(), error);
         }
-        let readme_file_name = "README.md";
-        let mut git_root = std::env::current_dir().unwrap();
-        while !git_root.join(readme_file_name).exists() {
-            if let Some(parent_path) = git_root.parent() {
-                git_root = parent_path.to_path_buf();
-            } else {
-                return;
-            }
-        }
-
-        let mut keys = results.keys().collect::<Vec<&u8>>();
-        keys.sort();
-
-        let readme_content = std::fs::read_to_string(git_root.join(readme_file_name)).unwrap();
-        let readme_lines = readme_content.split('\n').collect::<Vec<_>>();
-
-        let mut should_rewrite_readme = false;
-        let mut lines = Vec::new();
-        let longest_duration = Self::get_longest_duration(&results);
-
-        let mut reading_table = false;
-        let mut table_written = false;
-        for line in readme_lines {
-            if line.contains("{RESULTS_START}") {
-                reading_table = true;
-                lines.push(line.to_string());
-            } else if line.contains("{RESULTS_END}") {
-                reading_table = false;
-                lines.push(line.to_string());
-                continue;
-            } else if line.starts_with("<!-- {DAY ") {
-                let parts = line.split([' ', '}', '=']).collect::<Vec<_>>();
-                let day = parts[2].parse::<u8>().unwrap();
-                let mut stored_micros = parts[3].parse::<u128>().unwrap() as f64;
-
-                if let Some(result) = results.get(&day).and_then(|res| res.as_ref()) {
-                    let mut result_micros = result.elapsed.as_micros() as f64;
-                    if stored_micros > result_micros {
-                        (stored_micros, result_micros) = (result_micros, stored_micros);
-                    }
-                    if 1.0 - stored_micros / result_micros >= 0.1 {
-                        should_rewrite_readme = true;
-                    }
-                }
-            }
-
-            if reading_table && !table_written {
-                lines.push(format_args!("| Day | Status | Execution Time Comparison | Execution Time |\n").to_string());
-                lines.push(format_args!("|-----|--------|---------------------------|----------------|\n").to_string());
-                for day in &keys {
-                    let result = results.get(day).unwrap();
-                    let duration_label = match result {
-                        None => "",
-                        Some(result) => &format_elapsed(result.elapsed, false),
-                    };
-                    let status_badge = match result {
-                        None => "![Static Badge](https://img.shields.io/badge/Inconclusive-grey?style=flat)",
-                        Some(result) => match (result.part1_success, result.part2_success) {
-                            (Some(true), Some(true)) => "![Static Badge](https://img.shields.io/badge/Success-green?style=flat)",
-                            (Some(true), Some(false)) | (Some(false), Some(true)) => "![Static Badge](https://img.shields.io/badge/Failed-red?style=flat)",
-                            (None, Some(_)) | (Some(_), None) | _ => "![Static Badge](https://img.shields.io/badge/Inconclusive-grey?style=flat)"
-                        }
-                    };
-                    let progress_label = {
-                        if let Some(result) = result {
-                            let max_micros = longest_duration.as_micros();
-                            let result_micros = result.elapsed.as_micros();
-                            let percentage = 100 * result_micros / max_micros;
-                            format!("![Static Badge](https://progress-bar.xyz/{}/?width=500&progress_color=8935D9&progress_background=404040&show_text=false)", percentage)
-                        } else {
-                            "".to_string()
-                        }
-                    };
-                    let day_label = format!("[{}](src/days/day{}.rs)", day, day);
-                    lines.push(format_args!("|{}|{}|{}|{}|\n", day_label, status_badge, progress_label, duration_label.clear()).to_string());
-                }
+    }
 
-                for day in &keys {
-                    let result = results.get(day).unwrap();
-                    let duration_micros = {
-                        if let Some(result) = result {
-                            result.elapsed.as_micros()
-                        } else {
-                            u128::MAX
-                        }
-                    };
-                    lines.push(format_args!("<!-- {{DAY {}={}}} -->\n", day, duration_micros).to_string());
-                }
-                table_written = true;
-            } else if !reading_table {
-                lines.push(line.to_string());
-            }
-        }
+    pub fn print_history(&self, day: u8) {
+        TimingHistory::for_year(self.calendar.year()).print_day(day);
+    }
 
-        if should_rewrite_readme {
-            std::fs::write(git_root.join(readme_file_name), lines.iter().map(|s| s.trim()).collect::<Vec<_>>().join("\n")).unwrap();
+    pub fn export_history(&self, path: &str) {
+        match TimingHistory::for_year(self.calendar.year()).export_csv(Path::new(path)) {
+            Ok(count) => println!("Exported {} timing records to {}", count, path),
+            Err(error) => println!("{} {}", "Failed to export the timing history:".red().bold(), error),
         }
     }
 
-    fn get_longest_duration(results: &HashMap<u8, Option<TestRunResult>>) -> Duration {
-        let mut longest_duration = results.values()
-            .filter_map(|value| value.as_ref())
-            .map(|result| result.elapsed)
-            .max().unwrap_or(Duration::from_secs(0));
+    fn days(&self) -> impl Iterator<Item = u8> + '_ {
+        self.solutions.keys()
+            .filter(|(year, _)| *year == self.calendar.year())
+            .map(|(_, day)| *day)
+    }
 
-        if longest_duration.as_secs() < 5 {
-            longest_duration *= 6;
-            longest_duration /= 5;
+    fn check_date_and_print_link(&self) {
+        let now = self.clock.now();
+        let last_day_with_solution = self.days().max().unwrap_or(0);
+        if last_day_with_solution >= self.calendar.last_day() {
+            return;
         }
 
-        longest_duration
+        let next_day = last_day_with_solution + 1;
+        if self.calendar.is_unlocked(next_day, now) {
+            println!();
+            println!("{}: {}", "Your next AoC problem is ready! Grab it here".purple().bold().italic(), self.calendar.puzzle_url(next_day));
+        } else if let Some(remaining) = self.calendar.time_until_next_unlock(now)
+            && remaining < chrono::Duration::days(1) {
+            println!();
+            println!("{} {}", format!("Day {} unlocks in", next_day).purple().bold().italic(), format_countdown(remaining));
+        }
     }
 }
 
@@ -289,4 +306,50 @@ pub fn format_elapsed(duration: Duration, colorize: bool) -> String {
             result.red().bold().to_string()
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Display;
+    use chrono::{TimeZone, Utc};
+    use crate::utils::calendar::FixedClock;
+    use super::*;
+
+    struct Unsolvable;
+
+    impl Solution for Unsolvable {
+        fn solve(&self, _input: String) -> (Box<dyn Display>, Box<dyn Display>) {
+            panic!("The solution must not run");
+        }
+    }
+
+    #[test]
+    fn submit_waits_for_the_release() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 12, 1, 4, 59, 59).unwrap());
+        let mut aoc = AdventOfCode::default().with_clock(Box::new(clock));
+        aoc.add_solution(2025, 1, Box::new(Unsolvable));
+
+        aoc.submit_day(1, 1);
+    }
+
+    #[test]
+    fn select_year_switches_calendar_and_report() {
+        let mut aoc = AdventOfCode::default();
+        aoc.add_solution(2024, 3, Box::new(Unsolvable));
+        aoc.add_solution(2025, 1, Box::new(Unsolvable));
+        assert_eq!(aoc.days().collect::<Vec<_>>(), [1]);
+
+        aoc.select_year(2024);
+        assert_eq!((aoc.calendar.year(), aoc.calendar.last_day()), (2024, 25));
+        assert_eq!(aoc.report.default_year, 2024);
+        assert_eq!(aoc.days().collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "A solution has already been added for day 1 of 2025!")]
+    fn duplicate_solutions() {
+        let mut aoc = AdventOfCode::default();
+        aoc.add_solution(2025, 1, Box::new(Unsolvable));
+        aoc.add_solution(2025, 1, Box::new(Unsolvable));
+    }
+}
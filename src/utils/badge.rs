@@ -0,0 +1,281 @@
+use std::path::Path;
+use crate::utils::results_block::{write_atomic, ReportError};
+use crate::utils::test_set::TestRunResult;
+
+const BAR_WIDTH: usize = 500;
+const BAR_HEIGHT: usize = 20;
+const BAR_COLOR: &str = "#8935D9";
+const BAR_BACKGROUND: &str = "#404040";
+const UNICODE_BAR_WIDTH: usize = 25;
+
+/// Selects how status badges and execution time bars are rendered in the README.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ReportStyle {
+    /// Images served by `img.shields.io` and `progress-bar.xyz`.
+    Remote,
+    /// SVG images generated into the `docs` folder next to the README.
+    #[default]
+    LocalSvg,
+    /// Plain text status labels and Unicode block bars.
+    Unicode,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BadgeStatus {
+    Success,
+    Failed,
+    Inconclusive,
+}
+
+impl BadgeStatus {
+    pub fn from_result(result: &Option<TestRunResult>) -> Self {
+        match result {
+            None => BadgeStatus::Inconclusive,
+            Some(result) => match (result.part1_success, result.part2_success) {
+                (Some(true), Some(true)) => BadgeStatus::Success,
+                (Some(false), _) | (_, Some(false)) => BadgeStatus::Failed,
+                _ => BadgeStatus::Inconclusive,
+            }
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BadgeStatus::Success => "Success",
+            BadgeStatus::Failed => "Failed",
+            BadgeStatus::Inconclusive => "Inconclusive",
+        }
+    }
+
+    fn remote_color(&self) -> &'static str {
+        match self {
+            BadgeStatus::Success => "green",
+            BadgeStatus::Failed => "red",
+            BadgeStatus::Inconclusive => "grey",
+        }
+    }
+
+    fn svg_color(&self) -> &'static str {
+        match self {
+            BadgeStatus::Success => "#97CA00",
+            BadgeStatus::Failed => "#E05D44",
+            BadgeStatus::Inconclusive => "#9F9F9F",
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            BadgeStatus::Success => "✅",
+            BadgeStatus::Failed => "❌",
+            BadgeStatus::Inconclusive => "❔",
+        }
+    }
+}
+
+/// A generated image that has to be written next to the README before it references it.
+pub struct ReportAsset {
+    pub path: String,
+    pub content: String,
+}
+
+impl ReportAsset {
+    /// Writes the asset below `root`, leaving the file untouched if its content is unchanged.
+    pub fn write(&self, root: &Path) -> Result<(), ReportError> {
+        let path = root.join(&self.path);
+        if std::fs::read_to_string(&path).is_ok_and(|content| content == self.content) {
+            return Ok(());
+        }
+
+        write_atomic(&path, &self.content)
+    }
+}
+
+impl ReportStyle {
+    pub fn status_badge(&self, status: BadgeStatus, assets: &mut Vec<ReportAsset>) -> String {
+        match self {
+            ReportStyle::Remote => {
+                format!("![Static Badge](https://img.shields.io/badge/{}-{}?style=flat)", status.label(), status.remote_color())
+            }
+            ReportStyle::LocalSvg => {
+                let path = format!("docs/badges/{}.svg", status.label().to_lowercase());
+                let markdown = format!("![{}]({})", status.label(), path);
+                assets.push(ReportAsset { path, content: badge_svg(status.label(), status.svg_color()) });
+                markdown
+            }
+            ReportStyle::Unicode => format!("{} {}", status.symbol(), status.label()),
+        }
+    }
+
+    pub fn progress_bar(&self, year: i32, day: u8, percentage: u128, assets: &mut Vec<ReportAsset>) -> String {
+        let percentage = percentage.min(100) as usize;
+        match self {
+            ReportStyle::Remote => {
+                format!("![Static Badge](https://progress-bar.xyz/{}/?width={}&progress_color={}&progress_background={}&show_text=false)",
+                        percentage, BAR_WIDTH, &BAR_COLOR[1..], &BAR_BACKGROUND[1..])
+            }
+            ReportStyle::LocalSvg => {
+                let path = format!("docs/bars/{}/day{}.svg", year, day);
+                let markdown = format!("![{}%]({})", percentage, path);
+                assets.push(ReportAsset { path, content: progress_bar_svg(percentage) });
+                markdown
+            }
+            ReportStyle::Unicode => format!("`{}`", unicode_bar(percentage, UNICODE_BAR_WIDTH)),
+        }
+    }
+}
+
+/// Renders a flat, shields.io-like badge. Text width is estimated from the character count,
+/// so the output only depends on the label and color.
+pub fn badge_svg(label: &str, color: &str) -> String {
+    let width = label.chars().count() * 7 + 12;
+    format!(concat!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" role=\"img\" aria-label=\"{label}\">\n",
+        "  <title>{label}</title>\n",
+        "  <rect width=\"{width}\" height=\"20\" rx=\"3\" fill=\"{color}\"/>\n",
+        "  <text x=\"{center}\" y=\"14\" fill=\"#FFFFFF\" text-anchor=\"middle\" ",
+        "font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">{label}</text>\n",
+        "</svg>\n"),
+            width = width, center = width / 2, label = label, color = color)
+}
+
+pub fn progress_bar_svg(percentage: usize) -> String {
+    let filled = BAR_WIDTH * percentage.min(100) / 100;
+    format!(concat!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" role=\"img\" aria-label=\"{percentage}%\">\n",
+        "  <title>{percentage}%</title>\n",
+        "  <rect width=\"{width}\" height=\"{height}\" fill=\"{background}\"/>\n",
+        "  <rect width=\"{filled}\" height=\"{height}\" fill=\"{color}\"/>\n",
+        "</svg>\n"),
+            width = BAR_WIDTH, height = BAR_HEIGHT, percentage = percentage, filled = filled,
+            background = BAR_BACKGROUND, color = BAR_COLOR)
+}
+
+/// Renders a bar of `width` characters using eighth-block characters for the partial cell.
+pub fn unicode_bar(percentage: usize, width: usize) -> String {
+    const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    let eighths = width * 8 * percentage.min(100) / 100;
+    let mut bar = "█".repeat(eighths / 8);
+    let partial = eighths % 8;
+    if partial > 0 {
+        bar.push(PARTIAL_BLOCKS[partial]);
+    }
+    let filled = bar.chars().count();
+    bar.push_str(&" ".repeat(width - filled));
+
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    fn result(part1_success: Option<bool>, part2_success: Option<bool>) -> Option<TestRunResult> {
+        Some(TestRunResult {
+            test_name: "Test 1".to_string(),
+            elapsed: Duration::from_micros(56),
+            part1_elapsed: None,
+            part2_elapsed: None,
+            part1_result: String::new(),
+            part2_result: String::new(),
+            part1_success,
+            part2_success,
+            alloc_stats: None,
+        })
+    }
+
+    #[test]
+    fn status_from_result() {
+        assert_eq!(BadgeStatus::from_result(&None), BadgeStatus::Inconclusive);
+        assert_eq!(BadgeStatus::from_result(&result(Some(true), Some(true))), BadgeStatus::Success);
+        assert_eq!(BadgeStatus::from_result(&result(Some(true), Some(false))), BadgeStatus::Failed);
+        assert_eq!(BadgeStatus::from_result(&result(Some(false), Some(true))), BadgeStatus::Failed);
+        assert_eq!(BadgeStatus::from_result(&result(Some(true), None)), BadgeStatus::Inconclusive);
+        assert_eq!(BadgeStatus::from_result(&result(Some(false), Some(false))), BadgeStatus::Failed);
+        assert_eq!(BadgeStatus::from_result(&result(None, Some(false))), BadgeStatus::Failed);
+        assert_eq!(BadgeStatus::from_result(&result(None, None)), BadgeStatus::Inconclusive);
+    }
+
+    #[test]
+    fn status_badge_per_style() {
+        let mut assets = Vec::new();
+        assert_eq!(ReportStyle::Remote.status_badge(BadgeStatus::Failed, &mut assets),
+                   "![Static Badge](https://img.shields.io/badge/Failed-red?style=flat)");
+        assert_eq!(ReportStyle::Unicode.status_badge(BadgeStatus::Success, &mut assets), "✅ Success");
+        assert!(assets.is_empty());
+
+        assert_eq!(ReportStyle::LocalSvg.status_badge(BadgeStatus::Inconclusive, &mut assets),
+                   "![Inconclusive](docs/badges/inconclusive.svg)");
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].path, "docs/badges/inconclusive.svg");
+        assert_eq!(assets[0].content, badge_svg("Inconclusive", "#9F9F9F"));
+    }
+
+    #[test]
+    fn progress_bar_per_style() {
+        let mut assets = Vec::new();
+        assert_eq!(ReportStyle::Remote.progress_bar(2025, 2, 83, &mut assets),
+                   "![Static Badge](https://progress-bar.xyz/83/?width=500&progress_color=8935D9&progress_background=404040&show_text=false)");
+        assert_eq!(ReportStyle::Unicode.progress_bar(2025, 2, 40, &mut assets), format!("`{}`", "█".repeat(10) + &" ".repeat(15)));
+        assert!(assets.is_empty());
+
+        assert_eq!(ReportStyle::LocalSvg.progress_bar(2024, 7, 250, &mut assets), "![100%](docs/bars/2024/day7.svg)");
+        assert_eq!(assets[0].path, "docs/bars/2024/day7.svg");
+        assert_eq!(assets[0].content, progress_bar_svg(100));
+    }
+
+    #[test]
+    fn badge_svg_width_follows_the_label() {
+        let svg = badge_svg("Success", "#97CA00");
+        assert!(svg.contains("width=\"61\""));
+        assert!(svg.contains("x=\"30\""));
+        assert!(svg.contains("fill=\"#97CA00\""));
+        assert!(svg.contains(">Success</text>"));
+        assert_eq!(svg, badge_svg("Success", "#97CA00"));
+    }
+
+    #[test]
+    fn progress_bar_svg_fills_the_percentage() {
+        assert!(progress_bar_svg(0).contains("<rect width=\"0\" height=\"20\" fill=\"#8935D9\"/>"));
+        assert!(progress_bar_svg(83).contains("<rect width=\"415\" height=\"20\" fill=\"#8935D9\"/>"));
+        assert!(progress_bar_svg(120).contains("<rect width=\"500\" height=\"20\" fill=\"#8935D9\"/>"));
+    }
+
+    #[test]
+    fn unicode_bar_uses_partial_blocks() {
+        assert_eq!(unicode_bar(0, 4), "    ");
+        assert_eq!(unicode_bar(100, 4), "████");
+        assert_eq!(unicode_bar(150, 4), "████");
+        assert_eq!(unicode_bar(50, 3), "█▌ ");
+        assert_eq!(unicode_bar(10, 4), "▍   ");
+    }
+
+    #[test]
+    fn asset_is_only_written_when_changed() {
+        let dir = TestDir::new("badge-asset");
+        let asset = ReportAsset { path: "docs/badges/success.svg".to_string(), content: badge_svg("Success", "#97CA00") };
+        asset.write(dir.path()).unwrap();
+        let path = dir.join("docs/badges/success.svg");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), asset.content);
+
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        asset.write(dir.path()).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().modified().unwrap(), modified);
+
+        let changed = ReportAsset { path: asset.path.clone(), content: badge_svg("Failed", "#E05D44") };
+        changed.write(dir.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), changed.content);
+    }
+
+    #[test]
+    fn committed_readme_assets_match_the_renderer() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+        assert_eq!(read("docs/badges/success.svg"), badge_svg("Success", BadgeStatus::Success.svg_color()));
+        assert_eq!(read("docs/bars/2025/day1.svg"), progress_bar_svg(0));
+        assert_eq!(read("docs/bars/2025/day2.svg"), progress_bar_svg(83));
+    }
+}
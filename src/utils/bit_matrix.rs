@@ -0,0 +1,385 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, Not};
+use crate::utils::matrix::Matrix;
+use crate::utils::position::Position;
+
+const WORD_BITS: usize = 64;
+
+/// A fixed size grid of booleans packed 64 cells to a word. Every row starts at a new word, and
+/// the cell at `(x, y)` is bit `x % 64` of word `x / 64` of row `y`. Bits past the width are always zero.
+#[derive(Default, Clone, Eq, PartialEq, Hash)]
+pub struct BitMatrix {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    /// Sets every cell of `matrix` that matches the predicate.
+    pub fn from_matrix<T>(matrix: &Matrix<T>, f: impl Fn(&T) -> bool) -> Self {
+        let mut bits = Self::new(matrix.width(), matrix.height());
+        for y in 0..matrix.height() {
+            for (position, cell) in matrix.row_cells(y) {
+                if f(cell) {
+                    bits.set(position.x, position.y);
+                }
+            }
+        }
+
+        bits
+    }
+
+    pub fn to_matrix(&self) -> Matrix<bool> {
+        Matrix::new_fill(self.width, self.height, false).map_xy(|_, x, y| self.get(x, y))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether the cell is set, cells outside of the matrix never are.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.words[self.word_index(x, y)] & Self::bit(x) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize) {
+        let index = self.checked_word_index(x, y);
+        self.words[index] |= Self::bit(x);
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        let index = self.checked_word_index(x, y);
+        self.words[index] &= !Self::bit(x);
+    }
+
+    pub fn assign(&mut self, x: usize, y: usize, value: bool) {
+        if value {
+            self.set(x, y);
+        } else {
+            self.clear(x, y);
+        }
+    }
+
+    /// Sets the cell and returns whether it was already set.
+    pub fn test_and_set(&mut self, x: usize, y: usize) -> bool {
+        let index = self.checked_word_index(x, y);
+        let was_set = self.words[index] & Self::bit(x) != 0;
+        self.words[index] |= Self::bit(x);
+        was_set
+    }
+
+    /// Number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// The set cells in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        self.words.iter().enumerate().flat_map(move |(index, &word)| {
+            let y = index / self.words_per_row;
+            let x = (index % self.words_per_row) * WORD_BITS;
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                Some(Position::new(x + bit, y))
+            })
+        })
+    }
+
+    /// The words of row `y`, the lowest bit of the first word is column 0.
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Moves every cell by `(dx, dy)`, dropping cells that leave the matrix and clearing the cells
+    /// that were uncovered. Adding up the eight shifted copies of a grid gives neighbour counts
+    /// without looking at individual cells.
+    pub fn shifted(&self, dx: isize, dy: isize) -> BitMatrix {
+        let mut shifted = BitMatrix::new(self.width, self.height);
+        if dx.unsigned_abs() >= self.width || dy.unsigned_abs() >= self.height {
+            return shifted;
+        }
+
+        for y in 0..self.height {
+            let Some(source_y) = y.checked_add_signed(-dy).filter(|source_y| *source_y < self.height) else {
+                continue;
+            };
+
+            let source = self.row_words(source_y);
+            let target = &mut shifted.words[y * self.words_per_row..(y + 1) * self.words_per_row];
+            if dx >= 0 {
+                shift_row_right(source, target, dx as usize);
+            } else {
+                shift_row_left(source, target, dx.unsigned_abs());
+            }
+        }
+
+        shifted.mask_rows();
+        shifted
+    }
+
+    fn mask_rows(&mut self) {
+        let used_bits = self.width % WORD_BITS;
+        if used_bits == 0 {
+            return;
+        }
+
+        let mask = (1u64 << used_bits) - 1;
+        for y in 0..self.height {
+            self.words[(y + 1) * self.words_per_row - 1] &= mask;
+        }
+    }
+
+    fn assert_same_size(&self, other: &BitMatrix) {
+        if self.width != other.width || self.height != other.height {
+            panic!("Can't combine a {}x{} bit matrix with a {}x{} one", self.width, self.height, other.width, other.height);
+        }
+    }
+
+    fn bit(x: usize) -> u64 {
+        1 << (x % WORD_BITS)
+    }
+
+    fn word_index(&self, x: usize, y: usize) -> usize {
+        y * self.words_per_row + x / WORD_BITS
+    }
+
+    fn checked_word_index(&self, x: usize, y: usize) -> usize {
+        if x >= self.width || y >= self.height {
+            panic!("Position ({x}, {y}) is outside of the {}x{} bit matrix", self.width, self.height);
+        }
+
+        self.word_index(x, y)
+    }
+}
+
+/// Shifts the bits of a row towards higher columns.
+fn shift_row_right(source: &[u64], target: &mut [u64], amount: usize) {
+    let (words, bits) = (amount / WORD_BITS, amount % WORD_BITS);
+    for index in words..source.len() {
+        let low = source[index - words];
+        let carry = if bits > 0 && index > words { source[index - words - 1] >> (WORD_BITS - bits) } else { 0 };
+        target[index] = low << bits | carry;
+    }
+}
+
+/// Shifts the bits of a row towards lower columns.
+fn shift_row_left(source: &[u64], target: &mut [u64], amount: usize) {
+    let (words, bits) = (amount / WORD_BITS, amount % WORD_BITS);
+    for index in 0..source.len().saturating_sub(words) {
+        let high = source[index + words];
+        let carry = if bits > 0 && index + words + 1 < source.len() { source[index + words + 1] << (WORD_BITS - bits) } else { 0 };
+        target[index] = high >> bits | carry;
+    }
+}
+
+impl Index<Position> for BitMatrix {
+    type Output = bool;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        if self.get(position.x, position.y) { &true } else { &false }
+    }
+}
+
+impl Not for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn not(self) -> Self::Output {
+        let mut result = self.clone();
+        result.words.iter_mut().for_each(|word| *word = !*word);
+        result.mask_rows();
+        result
+    }
+}
+
+macro_rules! bit_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $operator:tt) => {
+        impl $assign_trait<&BitMatrix> for BitMatrix {
+            fn $assign_method(&mut self, other: &BitMatrix) {
+                self.assert_same_size(other);
+                self.words.iter_mut().zip(&other.words).for_each(|(word, other)| *word $operator *other);
+            }
+        }
+
+        impl $trait<&BitMatrix> for &BitMatrix {
+            type Output = BitMatrix;
+
+            fn $method(self, other: &BitMatrix) -> Self::Output {
+                let mut result = self.clone();
+                result.$assign_method(other);
+                result
+            }
+        }
+    };
+}
+
+bit_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+bit_operator!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+bit_operator!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+impl Debug for BitMatrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows = (0..self.height)
+            .map(|y| (0..self.width).map(|x| if self.get(x, y) { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::matrix::assert_matrix_eq;
+    use super::*;
+
+    /// Builds a bit matrix from rows of `#` and `.`.
+    fn bits(text: &str) -> BitMatrix {
+        BitMatrix::from_matrix(&Matrix::<char>::from_text(text), |cell| *cell == '#')
+    }
+
+    /// A copy of a single row matrix with column `x` cleared.
+    fn bits_without(matrix: &BitMatrix, x: usize) -> BitMatrix {
+        let mut matrix = matrix.clone();
+        matrix.clear(x, 0);
+        matrix
+    }
+
+    #[test]
+    fn sets_clears_and_assigns_cells() {
+        let mut matrix = BitMatrix::new(130, 2);
+        assert!(matrix.is_empty());
+
+        matrix.set(0, 0);
+        matrix.set(64, 0);
+        matrix.set(129, 1);
+        assert!(matrix.get(0, 0) && matrix.get(64, 0) && matrix.get(129, 1));
+        assert!(!matrix.get(63, 0) && !matrix.get(129, 0));
+        assert_eq!(matrix.count_ones(), 3);
+
+        matrix.clear(64, 0);
+        matrix.assign(1, 1, true);
+        matrix.assign(0, 0, false);
+        assert_eq!(matrix.positions().collect::<Vec<_>>(), vec![Position::new(1, 1), Position::new(129, 1)]);
+        assert!(matrix[Position::new(1, 1)]);
+        assert!(!matrix[Position::new(0, 0)]);
+    }
+
+    #[test]
+    fn cells_outside_are_never_set() {
+        let matrix = &!&BitMatrix::new(3, 2);
+        assert!(matrix.get(2, 1));
+        assert!(!matrix.get(3, 0));
+        assert!(!matrix.get(0, 2));
+        assert!(!matrix[Position::new(usize::MAX, 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Position (3, 0) is outside of the 3x2 bit matrix")]
+    fn set_outside_panics() {
+        BitMatrix::new(3, 2).set(3, 0);
+    }
+
+    #[test]
+    fn test_and_set_reports_the_previous_value() {
+        let mut matrix = BitMatrix::new(2, 2);
+        assert!(!matrix.test_and_set(1, 1));
+        assert!(matrix.test_and_set(1, 1));
+        assert_eq!(matrix.count_ones(), 1);
+    }
+
+    #[test]
+    fn converts_to_and_from_matrices() {
+        let matrix = bits("#..\n.#.\n..#\n");
+        assert_eq!(format!("{matrix:?}"), "#..\n.#.\n..#");
+        assert_matrix_eq!(matrix.to_matrix(), Matrix::<bool>::new(3, 3).map_xy(|_, x, y| x == y));
+    }
+
+    #[test]
+    fn positions_are_row_major() {
+        let matrix = bits(".#\n#.\n.#\n");
+        assert_eq!(matrix.positions().collect::<Vec<_>>(), vec![Position::new(1, 0), Position::new(0, 1), Position::new(1, 2)]);
+    }
+
+    #[test]
+    fn row_words_hold_the_bits_of_one_row() {
+        let mut matrix = BitMatrix::new(70, 2);
+        matrix.set(1, 1);
+        matrix.set(65, 1);
+        assert_eq!(matrix.row_words(0), &[0, 0]);
+        assert_eq!(matrix.row_words(1), &[0b10, 0b10]);
+    }
+
+    #[test]
+    fn negation_keeps_bits_past_the_width_clear() {
+        let matrix = !&BitMatrix::new(70, 2);
+        assert_eq!(matrix.count_ones(), 140);
+        assert_eq!(matrix.row_words(1), &[u64::MAX, 0b111111]);
+    }
+
+    #[test]
+    fn combines_matrices() {
+        let a = bits("##.\n...\n");
+        let b = bits(".##\n..#\n");
+        assert_eq!(&a & &b, bits(".#.\n...\n"));
+        assert_eq!(&a | &b, bits("###\n..#\n"));
+        assert_eq!(&a ^ &b, bits("#.#\n..#\n"));
+
+        let mut c = a.clone();
+        c |= &b;
+        c &= &bits("#.#\n#.#\n");
+        c ^= &bits("#..\n...\n");
+        assert_eq!(c, bits("..#\n..#\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't combine a 3x2 bit matrix with a 2x3 one")]
+    fn combining_different_sizes_panics() {
+        let _ = &BitMatrix::new(3, 2) | &BitMatrix::new(2, 3);
+    }
+
+    #[test]
+    fn shifts_within_the_matrix() {
+        let matrix = bits("#..\n.#.\n..#\n");
+        assert_eq!(matrix.shifted(1, 0), bits(".#.\n..#\n...\n"));
+        assert_eq!(matrix.shifted(-1, 1), bits("...\n...\n#..\n"));
+        assert_eq!(matrix.shifted(0, -2), bits("..#\n...\n...\n"));
+        assert!(matrix.shifted(3, 0).is_empty());
+        assert!(matrix.shifted(0, -3).is_empty());
+    }
+
+    #[test]
+    fn shifts_across_word_boundaries() {
+        let mut matrix = BitMatrix::new(200, 1);
+        for x in [0, 63, 64, 199] {
+            matrix.set(x, 0);
+        }
+
+        let right = matrix.shifted(70, 0);
+        assert_eq!(right.positions().map(|position| position.x).collect::<Vec<_>>(), vec![70, 133, 134]);
+        let left = matrix.shifted(-64, 0);
+        assert_eq!(left.positions().map(|position| position.x).collect::<Vec<_>>(), vec![0, 135]);
+        assert_eq!(matrix.shifted(1, 0).shifted(-1, 0), bits_without(&matrix, 199));
+    }
+}
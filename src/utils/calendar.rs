@@ -0,0 +1,181 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// Puzzles unlock at midnight in the AoC release timezone (UTC-5), which is 05:00 UTC.
+const RELEASE_HOUR_UTC: u32 = 5;
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock frozen at a single instant, for checking unlock logic at arbitrary dates.
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Release schedule of a single Advent of Code event.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EventCalendar {
+    year: i32,
+    days: u8,
+}
+
+impl Default for EventCalendar {
+    fn default() -> Self {
+        Self::for_year(2025)
+    }
+}
+
+impl EventCalendar {
+    pub fn new(year: i32, days: u8) -> Self {
+        if days == 0 || days > 25 {
+            panic!("An event must have between 1 and 25 days, got {days}!");
+        }
+
+        Self { year, days }
+    }
+
+    /// Returns the calendar of the given year, which has 12 days from 2025 onwards and 25 before.
+    pub fn for_year(year: i32) -> Self {
+        Self::new(year, if year >= 2025 { 12 } else { 25 })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn days(&self) -> u8 {
+        self.days
+    }
+
+    pub fn last_day(&self) -> u8 {
+        self.days
+    }
+
+    pub fn release_time(&self, day: u8) -> DateTime<Utc> {
+        if day == 0 || day > self.days {
+            panic!("Day {day} is not part of the {} event!", self.year);
+        }
+
+        Utc.with_ymd_and_hms(self.year, 12, day as u32, RELEASE_HOUR_UTC, 0, 0).unwrap()
+    }
+
+    pub fn is_unlocked(&self, day: u8, now: DateTime<Utc>) -> bool {
+        now >= self.release_time(day)
+    }
+
+    /// Returns the number of days that have been unlocked at the given instant.
+    pub fn unlocked_days(&self, now: DateTime<Utc>) -> u8 {
+        (1..=self.days)
+            .take_while(|day| self.is_unlocked(*day, now))
+            .count() as u8
+    }
+
+    /// Returns the next day to unlock and its release time, or `None` once every day is out.
+    pub fn next_unlock(&self, now: DateTime<Utc>) -> Option<(u8, DateTime<Utc>)> {
+        let next_day = self.unlocked_days(now) + 1;
+        if next_day > self.days {
+            return None;
+        }
+
+        Some((next_day, self.release_time(next_day)))
+    }
+
+    pub fn time_until_next_unlock(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.next_unlock(now).map(|(_, release_time)| release_time - now)
+    }
+
+    pub fn puzzle_url(&self, day: u8) -> String {
+        format!("https://adventofcode.com/{}/day/{}", self.year, day)
+    }
+}
+
+pub fn format_countdown(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (days, hours, minutes, seconds) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, month, day, hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn release_time_is_five_utc() {
+        let calendar = EventCalendar::for_year(2025);
+        assert_eq!(calendar.release_time(1), utc(12, 1, 5, 0, 0));
+        assert_eq!(calendar.release_time(12), utc(12, 12, 5, 0, 0));
+    }
+
+    #[test]
+    fn next_unlock_around_a_release() {
+        let calendar = EventCalendar::for_year(2025);
+        let before = FixedClock(utc(12, 3, 4, 59, 59));
+        let after = FixedClock(utc(12, 3, 5, 0, 0));
+
+        assert_eq!(calendar.next_unlock(before.now()), Some((3, utc(12, 3, 5, 0, 0))));
+        assert_eq!(calendar.time_until_next_unlock(before.now()), Some(Duration::seconds(1)));
+        assert!(!calendar.is_unlocked(3, before.now()));
+
+        assert_eq!(calendar.next_unlock(after.now()), Some((4, utc(12, 4, 5, 0, 0))));
+        assert_eq!(calendar.unlocked_days(after.now()), 3);
+        assert!(calendar.is_unlocked(3, after.now()));
+    }
+
+    #[test]
+    fn next_unlock_before_and_after_the_event() {
+        let calendar = EventCalendar::for_year(2025);
+        assert_eq!(calendar.next_unlock(FixedClock(utc(11, 20, 12, 0, 0)).now()), Some((1, utc(12, 1, 5, 0, 0))));
+        assert_eq!(calendar.next_unlock(FixedClock(utc(12, 12, 5, 0, 0)).now()), None);
+        assert_eq!(calendar.unlocked_days(FixedClock(utc(12, 31, 0, 0, 0)).now()), 12);
+    }
+
+    #[test]
+    fn event_length_by_year() {
+        assert_eq!(EventCalendar::for_year(2025).days(), 12);
+        assert_eq!(EventCalendar::for_year(2026).days(), 12);
+        assert_eq!(EventCalendar::for_year(2024).days(), 25);
+        assert_eq!(EventCalendar::for_year(2015).last_day(), 25);
+        assert_eq!(EventCalendar::for_year(2024).release_time(25), Utc.with_ymd_and_hms(2024, 12, 25, 5, 0, 0).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Day 13 is not part of the 2025 event!")]
+    fn release_time_past_the_last_day() {
+        EventCalendar::for_year(2025).release_time(13);
+    }
+
+    #[test]
+    fn countdown_format() {
+        assert_eq!(format_countdown(Duration::seconds(42)), "42s");
+        assert_eq!(format_countdown(Duration::seconds(125)), "2m 5s");
+        assert_eq!(format_countdown(Duration::seconds(3 * 3600 + 61)), "3h 1m 1s");
+        assert_eq!(format_countdown(Duration::seconds(2 * 86400 + 3600 + 60)), "2d 1h 1m");
+        assert_eq!(format_countdown(Duration::seconds(-5)), "0s");
+    }
+}
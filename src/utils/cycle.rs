@@ -0,0 +1,198 @@
+use std::hash::Hash;
+use ahash::{AHashMap, RandomState};
+
+/// A repeating sequence of states: the state after `start + length` steps equals the state after `start` steps.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step that has the same state as step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Remembers every visited state by its hash, so that the cycle is found the first time a state repeats.
+struct StateLog<S> {
+    hasher: RandomState,
+    states: Vec<S>,
+    steps_by_hash: AHashMap<u64, Vec<usize>>,
+}
+
+impl<S> StateLog<S>
+where
+    S: Hash + Eq,
+{
+    fn new(initial: S) -> Self {
+        let mut log = Self { hasher: RandomState::new(), states: Vec::new(), steps_by_hash: AHashMap::new() };
+        log.push(initial);
+        log
+    }
+
+    fn last(&self) -> &S {
+        self.states.last().unwrap()
+    }
+
+    fn find(&self, state: &S) -> Option<usize> {
+        self.steps_by_hash.get(&self.hasher.hash_one(state))?
+            .iter()
+            .copied()
+            .find(|&step| self.states[step] == *state)
+    }
+
+    fn push(&mut self, state: S) {
+        self.steps_by_hash.entry(self.hasher.hash_one(&state)).or_default().push(self.states.len());
+        self.states.push(state);
+    }
+}
+
+/// Runs `step` from `initial` until a state repeats, keeping every state in memory. Never returns
+/// when the states don't repeat.
+pub fn find_cycle<S>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle
+where
+    S: Hash + Eq,
+{
+    let mut log = StateLog::new(initial);
+    loop {
+        let next = step(log.last());
+        if let Some(start) = log.find(&next) {
+            return Cycle { start, length: log.states.len() - start };
+        }
+        log.push(next);
+    }
+}
+
+/// Returns the state after `n` steps, only simulating until the first repeated state. Works
+/// without a cycle as well, in which case all `n` steps are simulated.
+pub fn state_at<S>(initial: S, n: usize, mut step: impl FnMut(&S) -> S) -> S
+where
+    S: Hash + Eq,
+{
+    let mut log = StateLog::new(initial);
+    while log.states.len() <= n {
+        let next = step(log.last());
+        if let Some(start) = log.find(&next) {
+            let cycle = Cycle { start, length: log.states.len() - start };
+            return log.states.swap_remove(cycle.equivalent_step(n));
+        }
+        log.push(next);
+    }
+
+    log.states.pop().unwrap()
+}
+
+/// Finds the cycle with Brent's algorithm, which only keeps two states in memory at the cost of
+/// running `step` about three times as often. Never returns when the states don't repeat.
+pub fn find_cycle_brent<S>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle
+where
+    S: Eq + Clone,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Like `state_at`, but finds the cycle with Brent's algorithm and then simulates up to the
+/// equivalent step again. The states have to repeat eventually.
+pub fn state_at_brent<S>(initial: S, n: usize, mut step: impl FnMut(&S) -> S) -> S
+where
+    S: Eq + Clone,
+{
+    let cycle = find_cycle_brent(&initial, &mut step);
+    (0..cycle.equivalent_step(n)).fold(initial, |state, _| step(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use crate::utils::matrix::{assert_matrix_eq, Matrix};
+    use super::*;
+
+    /// Counts 0, 1, 2, 3, 4, 5 and then jumps back to 2, so the cycle starts at step 2 and has length 4.
+    fn step(state: &u32) -> u32 {
+        if *state < 5 { state + 1 } else { 2 }
+    }
+
+    #[test]
+    fn equivalent_step_folds_steps_into_the_cycle() {
+        let cycle = Cycle { start: 2, length: 4 };
+        assert_eq!(cycle.equivalent_step(0), 0);
+        assert_eq!(cycle.equivalent_step(5), 5);
+        assert_eq!(cycle.equivalent_step(6), 2);
+        assert_eq!(cycle.equivalent_step(1_000_000_001), 5);
+    }
+
+    #[test]
+    fn finds_the_cycle() {
+        assert_eq!(find_cycle(0, step), Cycle { start: 2, length: 4 });
+        assert_eq!(find_cycle_brent(&0, step), Cycle { start: 2, length: 4 });
+        assert_eq!(find_cycle(7, |state| *state), Cycle { start: 0, length: 1 });
+        assert_eq!(find_cycle_brent(&7, |state| *state), Cycle { start: 0, length: 1 });
+    }
+
+    #[test]
+    fn skips_to_the_state_at_step_n() {
+        for n in 0..20 {
+            let simulated = (0..n).fold(0, |state, _| step(&state));
+            assert_eq!(state_at(0, n, step), simulated, "step {n}");
+            assert_eq!(state_at_brent(0, n, step), simulated, "step {n}");
+        }
+        assert_eq!(state_at(0, 1_000_000_000, step), 4);
+        assert_eq!(state_at_brent(0, 1_000_000_000, step), 4);
+    }
+
+    #[test]
+    fn state_at_only_simulates_until_the_first_repeat() {
+        let calls = Cell::new(0);
+        let state = state_at(0, usize::MAX, |state| {
+            calls.set(calls.get() + 1);
+            step(state)
+        });
+        assert_eq!(state, step(&2));
+        assert_eq!(calls.get(), 6);
+    }
+
+    #[test]
+    fn state_at_works_without_a_cycle() {
+        assert_eq!(state_at(0u64, 100, |state| state + 1), 100);
+    }
+
+    #[test]
+    fn finds_cycles_of_matrices() {
+        let matrix = Matrix::<char>::from_text("ab\ncd\n");
+        assert_eq!(find_cycle(matrix.clone(), Matrix::rotate_cw), Cycle { start: 0, length: 4 });
+        assert_matrix_eq!(state_at(matrix.clone(), 1_000_000_001, Matrix::rotate_cw), matrix.rotate_cw());
+        assert_matrix_eq!(state_at_brent(matrix.clone(), 1_000_000_002, Matrix::rotate_cw), matrix.rotate_180());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::utils::position::Position;
+use crate::utils::vec2::Vec2;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Facing {
@@ -9,35 +10,86 @@ pub enum Facing {
 }
 
 impl Facing {
-    pub fn apply(&self, position: &Position) -> Position {
+    /// All facings clockwise, starting north.
+    pub const ALL: [Facing; 4] = [Facing::North, Facing::East, Facing::South, Facing::West];
+
+    /// The `(dx, dy)` step of one move in this direction, with y growing southwards.
+    pub fn offset(&self) -> (isize, isize) {
         match self {
-            Facing::East => Position { x: position.x + 1, y: position.y },
-            Facing::West => Position { x: position.x - 1, y: position.y },
-            Facing::South => Position { x: position.x, y: position.y + 1 },
-            Facing::North => Position { x: position.x, y: position.y - 1 },
+            Facing::East => (1, 0),
+            Facing::South => (0, 1),
+            Facing::West => (-1, 0),
+            Facing::North => (0, -1),
         }
     }
 
-    #[allow(dead_code)]
+    /// The step of one move in this direction as a vector.
+    pub fn delta(&self) -> Vec2 {
+        let (dx, dy) = self.offset();
+        Vec2::new(dx as i64, dy as i64)
+    }
+
+    /// Moves one step, panicking when that would leave the positive quadrant.
+    pub fn apply(&self, position: &Position) -> Position {
+        self.try_apply(position)
+            .unwrap_or_else(|| panic!("Can't move {:?} from ({}, {})", self, position.x, position.y))
+    }
+
     pub fn try_apply(&self, position: &Position) -> Option<Position> {
-        let new_position = match self {
-            Facing::East => (position.x as i64 + 1, position.y as i64 ),
-            Facing::West => (position.x as i64 - 1, position.y as i64 ),
-            Facing::South => (position.x as i64, position.y as i64 + 1 ),
-            Facing::North => (position.x as i64, position.y as i64 - 1 ),
-        };
+        Vec2::try_from(*position).ok().and_then(|vec| Position::try_from(vec + self.delta()).ok())
+    }
+
+    /// Parses arrows `^>v<`, compass letters `NESW` and `UDLR`.
+    pub fn from_char(ch: char) -> Option<Facing> {
+        match ch {
+            '^' | 'N' | 'U' => Some(Facing::North),
+            '>' | 'E' | 'R' => Some(Facing::East),
+            'v' | 'S' | 'D' => Some(Facing::South),
+            '<' | 'W' | 'L' => Some(Facing::West),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(&self) -> char {
+        match self {
+            Facing::North => '^',
+            Facing::East => '>',
+            Facing::South => 'v',
+            Facing::West => '<',
+        }
+    }
+
+    /// Turns by a number of quarter turns, clockwise for positive numbers.
+    pub fn turn(&self, quarter_turns: i32) -> Facing {
+        let index = Facing::ALL.iter().position(|facing| facing == self).unwrap() as i32;
+        Facing::ALL[(index + quarter_turns).rem_euclid(4) as usize]
+    }
 
-        if new_position.0 < 0 || new_position.1 < 0 {
-            None
-        } else {
-            Some(Position::new(new_position.0 as usize, new_position.1 as usize))
+    pub fn turn_left(&self) -> Facing {
+        self.turn(-1)
+    }
+
+    pub fn turn_right(&self) -> Facing {
+        self.turn(1)
+    }
+
+    pub fn turn_by(&self, turn: Turn) -> Facing {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
         }
     }
 
+    /// The two facings at a right angle, left first.
+    pub fn perpendicular(&self) -> [Facing; 2] {
+        [self.turn_left(), self.turn_right()]
+    }
+
     pub fn all() -> Vec<Facing> {
-        vec![Facing::North, Facing::East, Facing::South, Facing::West]
+        Facing::ALL.to_vec()
     }
 
+    /// All other facings, including the opposite one. See `perpendicular` for just the sides.
     pub fn adjacents(&self) -> Vec<Facing> {
         Facing::all()
             .into_iter()
@@ -53,4 +105,237 @@ impl Facing {
             Facing::North => Facing::South,
         }
     }
-}
\ No newline at end of file
+}
+
+/// A relative turn, as in `L` and `R` instructions.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Turn {
+    pub fn from_char(ch: char) -> Option<Turn> {
+        match ch {
+            'L' => Some(Turn::Left),
+            'R' => Some(Turn::Right),
+            _ => None,
+        }
+    }
+}
+
+/// One of the eight directions to the surrounding cells, including diagonals.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// All directions clockwise, starting north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North, Direction8::NorthEast, Direction8::East, Direction8::SouthEast,
+        Direction8::South, Direction8::SouthWest, Direction8::West, Direction8::NorthWest,
+    ];
+
+    /// The `(dx, dy)` step of one move in this direction, with y growing southwards.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn delta(&self) -> Vec2 {
+        let (dx, dy) = self.offset();
+        Vec2::new(dx as i64, dy as i64)
+    }
+
+    pub fn try_apply(&self, position: &Position) -> Option<Position> {
+        Vec2::try_from(*position).ok().and_then(|vec| Position::try_from(vec + self.delta()).ok())
+    }
+
+    /// Parses the characters of `Facing::from_char` and the digits of a numeric keypad, where
+    /// `8` is north, `9` north east and so on around `5`.
+    pub fn from_char(ch: char) -> Option<Direction8> {
+        match ch {
+            '8' => Some(Direction8::North),
+            '9' => Some(Direction8::NorthEast),
+            '6' => Some(Direction8::East),
+            '3' => Some(Direction8::SouthEast),
+            '2' => Some(Direction8::South),
+            '1' => Some(Direction8::SouthWest),
+            '4' => Some(Direction8::West),
+            '7' => Some(Direction8::NorthWest),
+            _ => Facing::from_char(ch).map(Direction8::from),
+        }
+    }
+
+    /// Turns by a number of eighth turns, clockwise for positive numbers.
+    pub fn turn(&self, eighth_turns: i32) -> Direction8 {
+        let index = Direction8::ALL.iter().position(|direction| direction == self).unwrap() as i32;
+        Direction8::ALL[(index + eighth_turns).rem_euclid(8) as usize]
+    }
+
+    pub fn turn_left(&self) -> Direction8 {
+        self.turn(-1)
+    }
+
+    pub fn turn_right(&self) -> Direction8 {
+        self.turn(1)
+    }
+
+    pub fn opposite(&self) -> Direction8 {
+        self.turn(4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.offset();
+        dx != 0 && dy != 0
+    }
+
+    /// The cardinal facing of the direction, `None` for diagonals.
+    pub fn facing(&self) -> Option<Facing> {
+        match self {
+            Direction8::North => Some(Facing::North),
+            Direction8::East => Some(Facing::East),
+            Direction8::South => Some(Facing::South),
+            Direction8::West => Some(Facing::West),
+            _ => None,
+        }
+    }
+}
+
+/// Fails with the character itself when `from_char` doesn't know it.
+impl TryFrom<char> for Facing {
+    type Error = char;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Facing::from_char(ch).ok_or(ch)
+    }
+}
+
+impl TryFrom<char> for Turn {
+    type Error = char;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Turn::from_char(ch).ok_or(ch)
+    }
+}
+
+impl TryFrom<char> for Direction8 {
+    type Error = char;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Direction8::from_char(ch).ok_or(ch)
+    }
+}
+
+impl From<Facing> for Direction8 {
+    fn from(facing: Facing) -> Self {
+        match facing {
+            Facing::North => Direction8::North,
+            Facing::East => Direction8::East,
+            Facing::South => Direction8::South,
+            Facing::West => Direction8::West,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_one_step() {
+        let position = Position::new(1, 1);
+        assert_eq!(Facing::North.apply(&position), Position::new(1, 0));
+        assert_eq!(Facing::East.apply(&position), Position::new(2, 1));
+        assert_eq!(Facing::South.apply(&position), Position::new(1, 2));
+        assert_eq!(Facing::West.apply(&position), Position::new(0, 1));
+        assert!(Facing::ALL.iter().all(|facing| Vec2::try_from(facing.apply(&position)).unwrap() - Vec2::try_from(position).unwrap() == facing.delta()));
+    }
+
+    #[test]
+    fn try_apply_stops_at_the_edge() {
+        let origin = Position::new(0, 0);
+        assert_eq!(Facing::North.try_apply(&origin), None);
+        assert_eq!(Facing::West.try_apply(&origin), None);
+        assert_eq!(Facing::East.try_apply(&origin), Some(Position::new(1, 0)));
+        assert_eq!(Direction8::NorthEast.try_apply(&Position::new(0, 1)), Some(Position::new(1, 0)));
+        assert_eq!(Direction8::SouthWest.try_apply(&Position::new(0, 1)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't move West from (0, 3)")]
+    fn apply_panics_at_the_edge() {
+        Facing::West.apply(&Position::new(0, 3));
+    }
+
+    #[test]
+    fn parses_facings() {
+        let facings = "^>v<NESWURDL".chars().map(|ch| Facing::from_char(ch).unwrap()).collect::<Vec<_>>();
+        assert_eq!(facings, [Facing::ALL, Facing::ALL, [Facing::North, Facing::East, Facing::South, Facing::West]].concat());
+        assert_eq!(Facing::from_char('x'), None);
+        assert_eq!(Facing::try_from('v'), Ok(Facing::South));
+        assert_eq!(Facing::try_from('n'), Err('n'));
+        assert!(Facing::ALL.iter().all(|facing| Facing::from_char(facing.arrow()) == Some(*facing)));
+    }
+
+    #[test]
+    fn parses_turns() {
+        assert_eq!(Turn::from_char('L'), Some(Turn::Left));
+        assert_eq!(Turn::try_from('R'), Ok(Turn::Right));
+        assert_eq!(Turn::try_from('U'), Err('U'));
+    }
+
+    #[test]
+    fn parses_directions() {
+        let keypad = "89632147".chars().map(|ch| Direction8::from_char(ch).unwrap()).collect::<Vec<_>>();
+        assert_eq!(keypad, Direction8::ALL);
+        assert_eq!(Direction8::from_char('<'), Some(Direction8::West));
+        assert_eq!(Direction8::try_from('U'), Ok(Direction8::North));
+        assert_eq!(Direction8::try_from('5'), Err('5'));
+    }
+
+    #[test]
+    fn turns() {
+        assert_eq!(Facing::North.turn_right(), Facing::East);
+        assert_eq!(Facing::North.turn_left(), Facing::West);
+        assert_eq!(Facing::East.turn(-6), Facing::West);
+        assert_eq!(Facing::South.turn_by(Turn::Left), Facing::East);
+        assert_eq!(Facing::South.perpendicular(), [Facing::East, Facing::West]);
+        assert_eq!(Facing::South.opposite(), Facing::North);
+        assert_eq!(Facing::South.adjacents(), [Facing::North, Facing::East, Facing::West]);
+
+        assert_eq!(Direction8::North.turn_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthEast.turn(10), Direction8::SouthWest);
+        assert_eq!(Direction8::NorthEast.opposite(), Direction8::SouthWest);
+    }
+
+    #[test]
+    fn directions_and_facings_agree() {
+        for facing in Facing::ALL {
+            let direction = Direction8::from(facing);
+            assert_eq!(direction.facing(), Some(facing));
+            assert_eq!(direction.offset(), facing.offset());
+            assert!(!direction.is_diagonal());
+        }
+        assert_eq!(Direction8::NorthWest.facing(), None);
+        assert!(Direction8::NorthWest.is_diagonal());
+        assert_eq!(Direction8::NorthWest.delta(), Facing::North.delta() + Facing::West.delta());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::utils::report::fnv1a;
+
+/// Describes the machine and build that produced a set of timings, so that timings are only
+/// compared when they were measured under the same conditions.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Fingerprint {
+    pub cpu_model: String,
+    pub cores: usize,
+    pub os: String,
+    pub rustc_version: String,
+    pub profile: String,
+}
+
+impl Fingerprint {
+    pub fn current() -> Self {
+        Self {
+            cpu_model: cpu_model().unwrap_or("Unknown CPU".to_string()),
+            cores: std::thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1),
+            os: os_name(),
+            rustc_version: env!("AOC_RUSTC_VERSION").to_string(),
+            profile: env!("AOC_BUILD_PROFILE").to_string(),
+        }
+    }
+
+    /// A short, stable identifier of the fingerprint.
+    pub fn id(&self) -> String {
+        format!("{:08x}", fnv1a(&self.summary()) as u32)
+    }
+
+    pub fn summary(&self) -> String {
+        format!("{} ({} threads), {}, {}, {}", self.cpu_model, self.cores, self.os, self.rustc_version, self.profile)
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    parse_cpu_model(&std::fs::read_to_string("/proc/cpuinfo").ok()?)
+}
+
+/// The first model name in the contents of `/proc/cpuinfo`, `Model` being used on ARM boards.
+fn parse_cpu_model(cpu_info: &str) -> Option<String> {
+    cpu_info.lines()
+        .find(|line| line.starts_with("model name") || line.starts_with("Model"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|model| !model.is_empty())
+}
+
+fn os_name() -> String {
+    let pretty_name = std::fs::read_to_string("/etc/os-release").ok().and_then(|os_release| parse_pretty_name(&os_release));
+
+    match pretty_name {
+        Some(name) => format!("{} {}", name, std::env::consts::ARCH),
+        None => format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+    }
+}
+
+fn parse_pretty_name(os_release: &str) -> Option<String> {
+    os_release.lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint() -> Fingerprint {
+        Fingerprint {
+            cpu_model: "AMD Ryzen 7 5800X 8-Core Processor".to_string(),
+            cores: 16,
+            os: "Ubuntu 24.04 LTS x86_64".to_string(),
+            rustc_version: "rustc 1.91.0".to_string(),
+            profile: "release".to_string(),
+        }
+    }
+
+    #[test]
+    fn summary_and_id() {
+        let fingerprint = fingerprint();
+        assert_eq!(fingerprint.summary(), "AMD Ryzen 7 5800X 8-Core Processor (16 threads), Ubuntu 24.04 LTS x86_64, rustc 1.91.0, release");
+        assert_eq!(fingerprint.id(), format!("{:08x}", fnv1a(&fingerprint.summary()) as u32));
+        assert_eq!(fingerprint.id().len(), 8);
+        assert_eq!(fingerprint.id(), fingerprint.clone().id());
+    }
+
+    #[test]
+    fn id_changes_with_the_build() {
+        let debug = Fingerprint { profile: "debug".to_string(), ..fingerprint() };
+        let fewer_cores = Fingerprint { cores: 8, ..fingerprint() };
+        assert_ne!(debug.id(), fingerprint().id());
+        assert_ne!(fewer_cores.id(), fingerprint().id());
+    }
+
+    #[test]
+    fn current_is_stable() {
+        let current = Fingerprint::current();
+        assert_eq!(current, Fingerprint::current());
+        assert!(current.cores >= 1);
+        assert_eq!(current.profile, env!("AOC_BUILD_PROFILE"));
+    }
+
+    #[test]
+    fn parses_cpu_models() {
+        let x86 = "processor\t: 0\nvendor_id\t: AuthenticAMD\nmodel\t\t: 33\nmodel name\t: AMD Ryzen 7   5800X 8-Core Processor\nprocessor\t: 1\nmodel name\t: Other\n";
+        assert_eq!(parse_cpu_model(x86).as_deref(), Some("AMD Ryzen 7 5800X 8-Core Processor"));
+        assert_eq!(parse_cpu_model("processor\t: 0\nModel\t\t: Raspberry Pi 4 Model B Rev 1.4\n").as_deref(), Some("Raspberry Pi 4 Model B Rev 1.4"));
+        assert_eq!(parse_cpu_model("model name\t:   \n"), None);
+        assert_eq!(parse_cpu_model(""), None);
+    }
+
+    #[test]
+    fn parses_os_names() {
+        assert_eq!(parse_pretty_name("NAME=\"Ubuntu\"\nPRETTY_NAME=\"Ubuntu 24.04 LTS\"\n").as_deref(), Some("Ubuntu 24.04 LTS"));
+        assert_eq!(parse_pretty_name("PRETTY_NAME=Alpine\n").as_deref(), Some("Alpine"));
+        assert_eq!(parse_pretty_name("NAME=Arch\n"), None);
+    }
+}
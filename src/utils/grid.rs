@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+use std::hash::Hash;
+use ahash::AHashSet;
+use crate::utils::bit_matrix::BitMatrix;
+use crate::utils::grid_n::{GridN, PointSet};
+use crate::utils::matrix::{Connectivity, Matrix};
+use crate::utils::pathfinding::{StateSearch, dijkstra};
+use crate::utils::point::Point;
+use crate::utils::position::Position;
+use crate::utils::sparse_grid::SparseGrid;
+
+/// What searches need to know about a grid of any dimension: the cell at a point and the cells
+/// next to it. Implemented by `Matrix`, `SparseGrid`, `GridN` and `PointSet`, so `flood_where`
+/// and `bfs` below work on all of them.
+pub trait Grid {
+    type Point: Copy + Eq + Hash;
+    type Cell;
+
+    /// The cell at `point`, `None` outside of the grid or for unset cells.
+    fn cell(&self, point: Self::Point) -> Option<&Self::Cell>;
+
+    /// The neighbouring cells of `point` that are part of the grid.
+    fn neighbors_of(&self, point: Self::Point, connectivity: Connectivity) -> impl Iterator<Item = (Self::Point, &Self::Cell)>;
+}
+
+impl<T> Grid for Matrix<T> {
+    type Point = Position;
+    type Cell = T;
+
+    fn cell(&self, point: Position) -> Option<&T> {
+        self.get(point.x, point.y)
+    }
+
+    fn neighbors_of(&self, point: Position, connectivity: Connectivity) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbors(point.x, point.y, connectivity)
+    }
+}
+
+impl<T> Grid for SparseGrid<T> {
+    type Point = (i64, i64);
+    type Cell = T;
+
+    fn cell(&self, (x, y): (i64, i64)) -> Option<&T> {
+        self.get(x, y)
+    }
+
+    fn neighbors_of(&self, (x, y): (i64, i64), connectivity: Connectivity) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.neighbors(x, y, connectivity)
+    }
+}
+
+impl<T, const N: usize> Grid for GridN<T, N> {
+    type Point = Point<N>;
+    type Cell = T;
+
+    fn cell(&self, point: Point<N>) -> Option<&T> {
+        self.get(&point)
+    }
+
+    fn neighbors_of(&self, point: Point<N>, connectivity: Connectivity) -> impl Iterator<Item = (Point<N>, &T)> {
+        self.neighbors(point, connectivity)
+    }
+}
+
+/// Every point in the set is a cell, every other point is outside of the grid.
+impl<const N: usize> Grid for PointSet<N> {
+    type Point = Point<N>;
+    type Cell = ();
+
+    fn cell(&self, point: Point<N>) -> Option<&()> {
+        self.contains(&point).then_some(&())
+    }
+
+    fn neighbors_of(&self, point: Point<N>, connectivity: Connectivity) -> impl Iterator<Item = (Point<N>, &())> {
+        point.neighbors(connectivity).filter(|neighbor| self.contains(neighbor)).map(|neighbor| (neighbor, &()))
+    }
+}
+
+/// The points a flood fill has already reached.
+pub trait Visited<P> {
+    fn contains(&self, point: &P) -> bool;
+
+    fn insert(&mut self, point: P);
+}
+
+impl<P: Eq + Hash> Visited<P> for AHashSet<P> {
+    fn contains(&self, point: &P) -> bool {
+        (**self).contains(point)
+    }
+
+    fn insert(&mut self, point: P) {
+        (**self).insert(point);
+    }
+}
+
+impl Visited<Position> for BitMatrix {
+    fn contains(&self, point: &Position) -> bool {
+        self[*point]
+    }
+
+    fn insert(&mut self, point: Position) {
+        self.set(point.x, point.y);
+    }
+}
+
+/// Fills the cells reachable from `start` on any grid. The predicate receives the filled cell
+/// being expanded from and the neighbouring cell, and decides whether the fill may step onto it.
+/// The fill uses a queue, so it handles regions of any size, and fills nothing when `start` is
+/// outside of the grid.
+pub fn flood_where<G, F>(grid: &G, start: G::Point, connectivity: Connectivity, f: F) -> AHashSet<G::Point>
+where
+    G: Grid,
+    F: FnMut(&G::Point, &G::Cell, &G::Point, &G::Cell) -> bool,
+{
+    let mut visited = AHashSet::new();
+    flood_into(grid, start, connectivity, &mut visited, f);
+    visited
+}
+
+/// Like `flood_where`, but adds the filled cells to `visited`, which also keeps the fill from
+/// entering the cells that are already in it.
+pub fn flood_into<G, V, F>(grid: &G, start: G::Point, connectivity: Connectivity, visited: &mut V, mut f: F)
+where
+    G: Grid,
+    V: Visited<G::Point>,
+    F: FnMut(&G::Point, &G::Cell, &G::Point, &G::Cell) -> bool,
+{
+    if grid.cell(start).is_none() {
+        return;
+    }
+
+    visited.insert(start);
+    let mut queue = VecDeque::from([start]);
+    while let Some(from) = queue.pop_front() {
+        let from_cell = grid.cell(from).unwrap();
+        for (to, to_cell) in grid.neighbors_of(from, connectivity) {
+            if !visited.contains(&to) && f(&from, from_cell, &to, to_cell) {
+                visited.insert(to);
+                queue.push_back(to);
+            }
+        }
+    }
+}
+
+/// Fills the cells equal to the one at `start` on any grid.
+pub fn flood_eq<G>(grid: &G, start: G::Point, connectivity: Connectivity) -> AHashSet<G::Point>
+where
+    G: Grid,
+    G::Cell: PartialEq,
+{
+    flood_where(grid, start, connectivity, |_, from, _, to| from == to)
+}
+
+/// Breadth first search from all `sources` at once on any grid, with the same predicate as
+/// `Matrix::bfs`. Sources outside of the grid are reached but never expanded.
+pub fn bfs<G, F>(grid: &G, sources: impl IntoIterator<Item = G::Point>, connectivity: Connectivity, mut passable: F) -> StateSearch<G::Point>
+where
+    G: Grid,
+    F: FnMut(&G::Point, &G::Cell, &G::Point, &G::Cell) -> bool,
+{
+    dijkstra(sources, |&from| {
+        let Some(from_cell) = grid.cell(from) else {
+            return Vec::new();
+        };
+
+        grid.neighbors_of(from, connectivity)
+            .filter(|(to, to_cell)| passable(&from, from_cell, to, to_cell))
+            .map(|(to, _)| (to, 1))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::point::BoundingBox;
+    use super::*;
+
+    const MAZE: &str = "#.###\n#...#\n###.#\n#...#\n#.###\n";
+
+    #[test]
+    fn floods_every_kind_of_grid() {
+        let matrix = Matrix::<char>::from_text(MAZE);
+        let sparse = SparseGrid::from_matrix(&matrix, |_| true);
+        let grid_n = GridN::<char, 2>::from_matrix(&matrix);
+        let points = PointSet::<2>::from_matrix(&matrix, |cell| *cell == '.');
+
+        let open = flood_eq(&matrix, Position::new(1, 0), Connectivity::Four);
+        assert_eq!(open.len(), 9);
+        let expected = open.iter().map(|position| (position.x as i64, position.y as i64)).collect::<AHashSet<_>>();
+        assert_eq!(flood_eq(&sparse, (1, 0), Connectivity::Four), expected);
+        assert_eq!(sparse.flood_eq(1, 0, Connectivity::Four), expected);
+
+        let expected = open.iter().map(|position| Point::from(*position)).collect::<AHashSet<_>>();
+        assert_eq!(flood_eq(&grid_n, Point([1, 0]), Connectivity::Four), expected);
+        assert_eq!(flood_where(&points, Point([1, 0]), Connectivity::Four, |_, _, _, _| true), expected);
+        assert_eq!(matrix.flood_where(1, 0, Connectivity::Four, |_, from, _, to| from == to).count_ones(), 9);
+    }
+
+    #[test]
+    fn flood_outside_of_the_grid_fills_nothing() {
+        let matrix = Matrix::<char>::from_text(MAZE);
+        assert!(flood_eq(&matrix, Position::new(5, 0), Connectivity::Four).is_empty());
+        assert!(matrix.flood_where(0, 5, Connectivity::Four, |_, _, _, _| true).is_empty());
+        assert!(flood_eq(&SparseGrid::<char>::new(), (0, 0), Connectivity::Eight).is_empty());
+    }
+
+    #[test]
+    fn flood_into_stops_at_visited_cells() {
+        let matrix = Matrix::<char>::from_text(MAZE);
+        let mut visited = BitMatrix::new(matrix.width(), matrix.height());
+        visited.set(3, 2);
+        flood_into(&matrix, Position::new(1, 0), Connectivity::Four, &mut visited, |_, from, _, to| from == to);
+        assert_eq!(visited.count_ones(), 5);
+        assert!(!visited.get(1, 4));
+
+        let mut visited = AHashSet::from([Position::new(1, 1)]);
+        flood_into(&matrix, Position::new(3, 3), Connectivity::Four, &mut visited, |_, from, _, to| from == to);
+        assert_eq!(visited.len(), 8);
+    }
+
+    #[test]
+    fn bfs_measures_distances_on_any_grid() {
+        let matrix = Matrix::<char>::from_text(MAZE);
+        let open = |_: &Position, _: &char, _: &Position, to: &char| *to == '.';
+        let search = bfs(&matrix, [Position::new(1, 0)], Connectivity::Four, open);
+        assert_eq!(search.distance(&Position::new(1, 4)), Some(8));
+        assert_eq!(search.distance(&Position::new(0, 0)), None);
+
+        let cube = PointSet::<3>::from_iter(BoundingBox::new(Point::ZERO, Point([2, 2, 2])).points());
+        let search = bfs(&cube, [Point::ZERO], Connectivity::Four, |_, _, _, _| true);
+        assert_eq!(search.distance(&Point([2, 2, 2])), Some(6));
+        let search = bfs(&cube, [Point::ZERO], Connectivity::Eight, |_, _, _, _| true);
+        assert_eq!(search.distance(&Point([2, 2, 2])), Some(2));
+        assert_eq!(search.reached().count(), 27);
+    }
+}
@@ -0,0 +1,305 @@
+use std::ops::{Index, IndexMut};
+use ahash::{AHashMap, AHashSet};
+use crate::utils::matrix::{Connectivity, Matrix};
+use crate::utils::point::{BoundingBox, Point};
+
+/// A dense grid over an N-dimensional box, the N-dimensional counterpart of `Matrix`. Points
+/// are signed and the box doesn't have to start at the origin.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct GridN<T, const N: usize> {
+    bounds: BoundingBox<N>,
+    data: Vec<T>,
+}
+
+impl<T, const N: usize> GridN<T, N> {
+    pub fn new_fill(bounds: BoundingBox<N>, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { bounds, data: vec![value; bounds.volume()] }
+    }
+
+    /// Embeds the matrix as the slice where every coordinate past y is 0.
+    pub fn from_matrix(matrix: &Matrix<T>) -> Self
+    where
+        T: Clone,
+    {
+        if N < 2 || matrix.width() == 0 || matrix.height() == 0 {
+            panic!("Can't embed a {}x{} matrix into {N} dimensions", matrix.width(), matrix.height());
+        }
+
+        let mut max = Point::ZERO;
+        max[0] = matrix.width() as i64 - 1;
+        max[1] = matrix.height() as i64 - 1;
+        Self { bounds: BoundingBox::new(Point::ZERO, max), data: matrix.as_slice().to_vec() }
+    }
+
+    pub fn bounds(&self) -> BoundingBox<N> {
+        self.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        self.bounds.contains(point)
+    }
+
+    pub fn get(&self, point: &Point<N>) -> Option<&T> {
+        self.bounds.index_of(point).map(|index| &self.data[index])
+    }
+
+    pub fn get_mut(&mut self, point: &Point<N>) -> Option<&mut T> {
+        self.bounds.index_of(point).map(|index| &mut self.data[index])
+    }
+
+    pub fn set(&mut self, point: &Point<N>, value: T) {
+        self[*point] = value;
+    }
+
+    /// All cells with their points, axis 0 changing fastest.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<N>, &T)> {
+        self.bounds.points().zip(&self.data)
+    }
+
+    pub fn count<F>(&self, f: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.data.iter().filter(|cell| f(cell)).count()
+    }
+
+    pub fn map<U, F>(&self, f: F) -> GridN<U, N>
+    where
+        F: Fn(&T) -> U,
+    {
+        GridN { bounds: self.bounds, data: self.data.iter().map(f).collect() }
+    }
+
+    /// The neighbours of `point` inside the grid, see `Point::offsets` for the order.
+    pub fn neighbors(&self, point: Point<N>, connectivity: Connectivity) -> impl Iterator<Item = (Point<N>, &T)> {
+        point.neighbors(connectivity).filter_map(|neighbor| self.get(&neighbor).map(|cell| (neighbor, cell)))
+    }
+
+    /// Copies the grid into a new box, filling the points that weren't in the grid with `empty`.
+    pub fn resized(&self, bounds: BoundingBox<N>, empty: T) -> Self
+    where
+        T: Clone,
+    {
+        let mut grid = Self::new_fill(bounds, empty);
+        for (point, cell) in self.iter() {
+            if let Some(target) = grid.get_mut(&point) {
+                *target = cell.clone();
+            }
+        }
+
+        grid
+    }
+}
+
+impl<T, const N: usize> Index<Point<N>> for GridN<T, N> {
+    type Output = T;
+
+    fn index(&self, point: Point<N>) -> &Self::Output {
+        match self.bounds.index_of(&point) {
+            Some(index) => &self.data[index],
+            None => panic!("Point {:?} is outside of the grid {:?}", point.0, self.bounds),
+        }
+    }
+}
+
+impl<T, const N: usize> IndexMut<Point<N>> for GridN<T, N> {
+    fn index_mut(&mut self, point: Point<N>) -> &mut Self::Output {
+        match self.bounds.index_of(&point) {
+            Some(index) => &mut self.data[index],
+            None => panic!("Point {:?} is outside of the grid {:?}", point.0, self.bounds),
+        }
+    }
+}
+
+/// An unbounded set of N-dimensional points, for the active cells of an automaton that keeps
+/// growing. The N-dimensional counterpart of a `SparseGrid` without values.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PointSet<const N: usize> {
+    points: AHashSet<Point<N>>,
+}
+
+impl<const N: usize> Default for PointSet<N> {
+    fn default() -> Self {
+        Self { points: AHashSet::new() }
+    }
+}
+
+impl<const N: usize> PointSet<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The points of the cells of `matrix` that match the predicate, with every coordinate past y set to 0.
+    pub fn from_matrix<T>(matrix: &Matrix<T>, f: impl Fn(&T) -> bool) -> Self {
+        (0..matrix.height())
+            .flat_map(|y| matrix.row_cells(y))
+            .filter(|(_, cell)| f(cell))
+            .map(|(position, _)| Point::from_position(position))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        self.points.contains(point)
+    }
+
+    /// Adds the point and returns whether it was new.
+    pub fn insert(&mut self, point: Point<N>) -> bool {
+        self.points.insert(point)
+    }
+
+    /// Removes the point and returns whether it was in the set.
+    pub fn remove(&mut self, point: &Point<N>) -> bool {
+        self.points.remove(point)
+    }
+
+    /// All points, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Point<N>> {
+        self.points.iter()
+    }
+
+    /// The smallest box containing every point, `None` for an empty set.
+    pub fn bounds(&self) -> Option<BoundingBox<N>> {
+        BoundingBox::from_points(&self.points)
+    }
+
+    /// How many points of the set are next to each point, only listing points with at least one.
+    pub fn neighbor_counts(&self, connectivity: Connectivity) -> AHashMap<Point<N>, usize> {
+        let offsets = Point::offsets(connectivity);
+        let mut counts = AHashMap::with_capacity(self.points.len() * offsets.len());
+        for point in &self.points {
+            for offset in &offsets {
+                *counts.entry(*point + *offset).or_insert(0) += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// One step of a cellular automaton. `rule` receives whether a point is in the set and how
+    /// many of its neighbours are, and decides whether it is in the next set. Points without
+    /// neighbours in the set are never added.
+    pub fn step(&self, connectivity: Connectivity, rule: impl Fn(bool, usize) -> bool) -> Self {
+        let counts = self.neighbor_counts(connectivity);
+        let mut next = counts.iter()
+            .filter(|(point, count)| rule(self.contains(point), **count))
+            .map(|(point, _)| *point)
+            .collect::<Self>();
+        if rule(true, 0) {
+            next.points.extend(self.points.iter().filter(|point| !counts.contains_key(point)));
+        }
+
+        next
+    }
+}
+
+impl<const N: usize> FromIterator<Point<N>> for PointSet<N> {
+    fn from_iter<I: IntoIterator<Item = Point<N>>>(iter: I) -> Self {
+        Self { points: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The starting slice of the Conway cubes puzzle.
+    const GLIDER: &str = ".#.\n..#\n###\n";
+
+    fn conway<const N: usize>(cycles: usize) -> usize {
+        let mut active = PointSet::<N>::from_matrix(&Matrix::<char>::from_text(GLIDER), |cell| *cell == '#');
+        for _ in 0..cycles {
+            active = active.step(Connectivity::Eight, |active, neighbors| neighbors == 3 || active && neighbors == 2);
+        }
+
+        active.len()
+    }
+
+    #[test]
+    fn embeds_matrices() {
+        let grid = GridN::<char, 3>::from_matrix(&Matrix::from_text(GLIDER));
+        assert_eq!(grid.bounds(), BoundingBox::new(Point([0, 0, 0]), Point([2, 2, 0])));
+        assert_eq!(grid.len(), 9);
+        assert_eq!(grid[Point([1, 0, 0])], '#');
+        assert_eq!(grid.get(&Point([1, 0, 1])), None);
+        assert_eq!(grid.count(|cell| *cell == '#'), 5);
+
+        let points = PointSet::<3>::from_matrix(&Matrix::<char>::from_text(GLIDER), |cell| *cell == '#');
+        assert!(points.contains(&Point([2, 1, 0])));
+        assert_eq!(points.bounds(), Some(BoundingBox::new(Point([0, 0, 0]), Point([2, 2, 0]))));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't embed a 0x0 matrix into 3 dimensions")]
+    fn embedding_an_empty_matrix_panics() {
+        GridN::<char, 3>::from_matrix(&Matrix::default());
+    }
+
+    #[test]
+    fn grids_are_indexed_by_signed_points() {
+        let mut grid = GridN::new_fill(BoundingBox::new(Point([-1, -1]), Point([1, 1])), 0);
+        grid.set(&Point([-1, 1]), 5);
+        *grid.get_mut(&Point([0, 0])).unwrap() = 7;
+        assert_eq!(grid.iter().filter(|(_, cell)| **cell > 0).map(|(point, _)| point).collect::<Vec<_>>(), [Point([0, 0]), Point([-1, 1])]);
+        assert_eq!(grid.neighbors(Point([-1, -1]), Connectivity::Four).count(), 2);
+        assert_eq!(grid.neighbors(Point([0, 0]), Connectivity::Eight).count(), 8);
+        assert_eq!(grid.map(|cell| cell * 2)[Point([-1, 1])], 10);
+
+        let resized = grid.resized(BoundingBox::new(Point([0, 0]), Point([2, 2])), 9);
+        assert_eq!((resized[Point([0, 0])], resized[Point([2, 2])]), (7, 9));
+        assert_eq!(resized.get(&Point([-1, 1])), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Point [2, 0] is outside of the grid")]
+    fn indexing_outside_panics() {
+        let grid = GridN::new_fill(BoundingBox::new(Point([0, 0]), Point([1, 1])), 0);
+        let _ = grid[Point([2, 0])];
+    }
+
+    #[test]
+    fn point_sets() {
+        let mut points = PointSet::<2>::new();
+        assert!(points.is_empty());
+        assert!(points.insert(Point([0, 0])));
+        assert!(!points.insert(Point([0, 0])));
+        points.insert(Point([2, 0]));
+
+        let counts = points.neighbor_counts(Connectivity::Four);
+        assert_eq!(counts[&Point([1, 0])], 2);
+        assert_eq!(counts.get(&Point([0, 0])), None);
+        assert!(points.remove(&Point([2, 0])));
+        assert_eq!(points.iter().collect::<Vec<_>>(), [&Point([0, 0])]);
+    }
+
+    #[test]
+    fn step_keeps_isolated_points_when_the_rule_says_so() {
+        let points = [Point([0, 0]), Point([5, 5])].into_iter().collect::<PointSet<2>>();
+        assert_eq!(points.step(Connectivity::Eight, |active, _| active).len(), 2);
+        assert!(points.step(Connectivity::Eight, |active, neighbors| active && neighbors > 0).is_empty());
+    }
+
+    #[test]
+    fn runs_conway_cubes() {
+        assert_eq!(conway::<3>(6), 112);
+        assert_eq!(conway::<4>(6), 848);
+    }
+}
@@ -0,0 +1,328 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use crate::utils::aoc::format_elapsed;
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::solution::data_path;
+use crate::utils::test_set::TestRunResult;
+
+const HISTORY_FILE_NAME: &str = "history.tsv";
+const SPARKLINE_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A single execution of a solution, as stored in the timing history.
+#[derive(Debug, Clone)]
+pub struct TimingRecord {
+    pub date: DateTime<Utc>,
+    pub commit: Option<String>,
+    pub machine: String,
+    /// Summary of the machine fingerprint, empty for records written before it was tracked.
+    pub fingerprint: String,
+    pub year: i32,
+    pub day: u8,
+    pub test_name: String,
+    pub elapsed: Duration,
+    pub part1_elapsed: Option<Duration>,
+    pub part2_elapsed: Option<Duration>,
+}
+
+impl TimingRecord {
+    pub fn new(year: i32, day: u8, result: &TestRunResult, commit: Option<String>, machine: &str, fingerprint: &Fingerprint) -> Self {
+        Self {
+            date: Utc::now(),
+            commit,
+            machine: machine.to_string(),
+            fingerprint: fingerprint.summary(),
+            year,
+            day,
+            test_name: result.test_name.clone(),
+            elapsed: result.elapsed,
+            part1_elapsed: result.part1_elapsed,
+            part2_elapsed: result.part2_elapsed,
+        }
+    }
+
+    fn fields(&self) -> Vec<String> {
+        let format_micros = |elapsed: Option<Duration>| elapsed.map(|elapsed| elapsed.as_micros().to_string()).unwrap_or_default();
+        vec![
+            self.date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            self.commit.clone().unwrap_or_default(),
+            self.machine.clone(),
+            self.year.to_string(),
+            self.day.to_string(),
+            self.test_name.clone(),
+            self.elapsed.as_micros().to_string(),
+            format_micros(self.part1_elapsed),
+            format_micros(self.part2_elapsed),
+            self.fingerprint.clone(),
+        ]
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        if fields.len() != 9 && fields.len() != 10 {
+            return None;
+        }
+
+        let parse_micros = |field: &str| -> Option<Option<Duration>> {
+            match field {
+                "" => Some(None),
+                _ => field.parse::<u64>().ok().map(|micros| Some(Duration::from_micros(micros))),
+            }
+        };
+
+        Some(Self {
+            date: DateTime::parse_from_rfc3339(fields[0]).ok()?.with_timezone(&Utc),
+            commit: Some(fields[1].to_string()).filter(|commit| !commit.is_empty()),
+            machine: fields[2].to_string(),
+            year: fields[3].parse().ok()?,
+            day: fields[4].parse().ok()?,
+            test_name: fields[5].to_string(),
+            elapsed: parse_micros(fields[6])??,
+            part1_elapsed: parse_micros(fields[7])?,
+            part2_elapsed: parse_micros(fields[8])?,
+            fingerprint: fields.get(9).unwrap_or(&"").to_string(),
+        })
+    }
+}
+
+/// Append-only log of solution timings, stored as tab separated lines in `data/<year>/history.tsv`.
+pub struct TimingHistory {
+    path: PathBuf,
+}
+
+impl TimingHistory {
+    pub fn for_year(year: i32) -> Self {
+        Self { path: data_path(year).join(HISTORY_FILE_NAME) }
+    }
+
+    pub fn append(&self, records: &[TimingRecord]) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let lines = records.iter()
+            .map(|record| format!("{}\n", record.fields().join("\t")))
+            .collect::<String>();
+
+        std::io::Write::write_all(&mut file, lines.as_bytes())
+    }
+
+    /// Reads every record, skipping lines that can't be parsed.
+    pub fn load(&self) -> Vec<TimingRecord> {
+        let Ok(content) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+
+        content.lines()
+            .filter_map(|line| {
+                let record = TimingRecord::parse(line);
+                if record.is_none() && !line.trim().is_empty() {
+                    println!("{} {}", "Skipping invalid history record:".yellow(), line);
+                }
+                record
+            })
+            .collect()
+    }
+
+    pub fn export_csv(&self, path: &Path) -> std::io::Result<usize> {
+        let records = self.load();
+        let mut csv = String::from("date,commit,machine,year,day,test,total_us,part1_us,part2_us,fingerprint\n");
+        for record in &records {
+            let fields = record.fields().iter().map(|field| csv_escape(field)).collect::<Vec<_>>();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        std::fs::write(path, csv)?;
+        Ok(records.len())
+    }
+
+    /// Prints a sparkline of the execution times of a day for each machine and fingerprint found in
+    /// the history, since timings measured under different conditions can't be compared.
+    pub fn print_day(&self, day: u8) {
+        let records = self.load().into_iter()
+            .filter(|record| record.day == day)
+            .collect::<Vec<_>>();
+        if records.is_empty() {
+            println!("No timing history found for day {} in {}", day, self.path.display());
+            return;
+        }
+
+        let mut machines = records.iter().map(|record| (record.machine.as_str(), record.fingerprint.as_str())).collect::<Vec<_>>();
+        machines.sort();
+        machines.dedup();
+
+        for (machine, fingerprint) in machines {
+            let timings = records.iter()
+                .filter(|record| record.machine == machine && record.fingerprint == fingerprint)
+                .collect::<Vec<_>>();
+            let elapsed = timings.iter().map(|record| record.elapsed).collect::<Vec<_>>();
+            let first = timings.first().unwrap();
+            let last = timings.last().unwrap();
+
+            println!("{} {} ({} runs, {} to {})", "Machine".bold(), machine.purple(), timings.len(),
+                     first.date.format("%Y-%m-%d"), last.date.format("%Y-%m-%d"));
+            if !fingerprint.is_empty() {
+                println!("   {}", fingerprint.italic());
+            }
+            println!("   {}", sparkline(&elapsed).bright_purple());
+            println!("   min {}  max {}  last {}{}",
+                     format_elapsed(*elapsed.iter().min().unwrap(), true),
+                     format_elapsed(*elapsed.iter().max().unwrap(), true),
+                     format_elapsed(last.elapsed, true),
+                     last.commit.as_ref().map(|commit| format!(" ({})", commit)).unwrap_or_default());
+        }
+    }
+}
+
+pub fn sparkline(values: &[Duration]) -> String {
+    let min = values.iter().min().copied().unwrap_or_default().as_secs_f64();
+    let max = values.iter().max().copied().unwrap_or_default().as_secs_f64();
+
+    values.iter()
+        .map(|value| {
+            if max <= min {
+                return SPARKLINE_BLOCKS[0];
+            }
+            let level = (value.as_secs_f64() - min) / (max - min) * (SPARKLINE_BLOCKS.len() - 1) as f64;
+            SPARKLINE_BLOCKS[level.round() as usize]
+        })
+        .collect()
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Returns the abbreviated hash of the checked out commit, if the project is a git repository.
+pub fn current_commit() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if commit.is_empty() { None } else { Some(commit) }
+}
+
+/// Returns an identifier of the current machine, based on `/etc/machine-id` or the host name.
+pub fn machine_id() -> String {
+    if let Ok(id) = std::fs::read_to_string("/etc/machine-id") && !id.trim().is_empty() {
+        return id.trim().chars().take(12).collect();
+    }
+
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or("unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    fn record(day: u8, micros: u64, machine: &str) -> TimingRecord {
+        TimingRecord {
+            date: Utc.with_ymd_and_hms(2025, 12, day as u32, 6, 0, 0).unwrap(),
+            commit: Some("abc1234".to_string()),
+            machine: machine.to_string(),
+            fingerprint: "Test CPU (4 threads), Test OS".to_string(),
+            year: 2025,
+            day,
+            test_name: "Test 1".to_string(),
+            elapsed: Duration::from_micros(micros),
+            part1_elapsed: Some(Duration::from_micros(micros / 4)),
+            part2_elapsed: None,
+        }
+    }
+
+    fn assert_same(a: &TimingRecord, b: &TimingRecord) {
+        assert_eq!(a.fields(), b.fields());
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let record = record(2, 31355, "machine");
+        let line = record.fields().join("\t");
+        assert_eq!(line, "2025-12-02T06:00:00Z\tabc1234\tmachine\t2025\t2\tTest 1\t31355\t7838\t\tTest CPU (4 threads), Test OS");
+        assert_same(&TimingRecord::parse(&line).unwrap(), &record);
+    }
+
+    #[test]
+    fn parses_records_without_fingerprint() {
+        let parsed = TimingRecord::parse("2025-12-01T06:00:00Z\t\tmachine\t2025\t1\tTest 1\t56\t\t").unwrap();
+        assert_eq!((parsed.commit, parsed.fingerprint.as_str()), (None, ""));
+        assert_eq!((parsed.elapsed, parsed.part1_elapsed), (Duration::from_micros(56), None));
+    }
+
+    #[test]
+    fn rejects_invalid_records() {
+        let valid = record(1, 56, "machine").fields();
+        let with_field = |index: usize, value: &str| {
+            let mut fields = valid.clone();
+            fields[index] = value.to_string();
+            fields.join("\t")
+        };
+
+        assert!(TimingRecord::parse(&valid[..8].join("\t")).is_none());
+        assert!(TimingRecord::parse(&format!("{}\textra", valid.join("\t"))).is_none());
+        assert!(TimingRecord::parse(&with_field(0, "yesterday")).is_none());
+        assert!(TimingRecord::parse(&with_field(4, "day1")).is_none());
+        assert!(TimingRecord::parse(&with_field(6, "")).is_none());
+        assert!(TimingRecord::parse(&with_field(7, "-5")).is_none());
+    }
+
+    #[test]
+    fn appends_and_loads_records() {
+        let dir = TestDir::new("history");
+        let history = TimingHistory { path: dir.join("2025/history.tsv") };
+        assert!(history.load().is_empty());
+
+        history.append(&[record(1, 56, "a"), record(2, 31355, "a")]).unwrap();
+        history.append(&[record(1, 60, "b")]).unwrap();
+        let mut content = std::fs::read_to_string(&history.path).unwrap();
+        content.push_str("not a record\n\n");
+        std::fs::write(&history.path, content).unwrap();
+
+        let records = history.load();
+        assert_eq!(records.len(), 3);
+        assert_same(&records[2], &record(1, 60, "b"));
+        history.print_day(1);
+        history.print_day(3);
+    }
+
+    #[test]
+    fn exports_csv() {
+        let dir = TestDir::new("history-csv");
+        let history = TimingHistory { path: dir.join("history.tsv") };
+        let mut quoted = record(1, 56, "my \"fast\" pc");
+        quoted.test_name = "Test 1, small".to_string();
+        history.append(&[quoted]).unwrap();
+
+        assert_eq!(history.export_csv(&dir.join("history.csv")).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(dir.join("history.csv")).unwrap(), concat!(
+            "date,commit,machine,year,day,test,total_us,part1_us,part2_us,fingerprint\n",
+            "2025-12-01T06:00:00Z,abc1234,\"my \"\"fast\"\" pc\",2025,1,\"Test 1, small\",56,14,,\"Test CPU (4 threads), Test OS\"\n"));
+    }
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        let micros = |values: &[u64]| values.iter().map(|micros| Duration::from_micros(*micros)).collect::<Vec<_>>();
+        assert_eq!(sparkline(&micros(&[10, 20, 80, 45])), "▁▂█▅");
+        assert_eq!(sparkline(&micros(&[5, 5])), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
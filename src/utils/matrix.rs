@@ -1,14 +1,75 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use crate::utils::bit_matrix::BitMatrix;
+use crate::utils::facing::Facing;
+use crate::utils::grid;
+use crate::utils::position::Position;
 
-#[derive(Default, Clone)]
+/// Offsets of the four orthogonal neighbours, clockwise starting north.
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+/// Offsets of the eight surrounding cells, clockwise starting north.
+const OFFSETS_8: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// Which cells count as adjacent when filling or labelling regions.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Orthogonal neighbours only. For a `Point<N>` these are the 2N neighbours sharing a face.
+    #[default]
+    Four,
+    /// Orthogonal and diagonal neighbours. For a `Point<N>` these are all 3^N - 1 surrounding points.
+    Eight,
+}
+
+impl Connectivity {
+    /// The `(dx, dy)` steps to the adjacent cells, clockwise starting north.
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &OFFSETS_4,
+            Connectivity::Eight => &OFFSETS_8,
+        }
+    }
+}
+
+/// A connected region of equal cells found by `Matrix::components`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Region {
+    pub label: u32,
+    /// The first cell of the region in row-major order.
+    pub start: Position,
+    pub area: usize,
+    /// Number of cell edges between the region and other cells or the outside of the matrix.
+    pub perimeter: usize,
+    /// Number of straight fence segments around the region, holes included.
+    pub sides: usize,
+}
+
+/// Result of `Matrix::components`, `labels` holds the index into `regions` for every cell.
+#[derive(Debug, Clone)]
+pub struct Components {
+    pub labels: Matrix<u32>,
+    pub regions: Vec<Region>,
+}
+
+/// A cell that differs between two matrices, see `Matrix::diff`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CellChange<'a, T> {
+    pub position: Position,
+    pub left: Option<&'a T>,
+    pub right: Option<&'a T>,
+}
+
+/// A fixed size grid stored as a single row-major `Vec`, the cell at `(x, y)` lives at `y * width + x`.
+#[derive(Default, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Matrix<T> {
     width: usize,
     height: usize,
-    data: Vec<Vec<T>>,
+    data: Vec<T>,
 }
 
-#[allow(dead_code)]
 impl<T> Matrix<T>
 where
     T: Default + Clone,
@@ -17,43 +78,27 @@ where
         Self {
             width,
             height,
-            data: vec![vec![T::default(); width]; height],
+            data: vec![T::default(); width * height],
         }
     }
 
     pub fn map<T2>(&self, f: impl Fn(&T) -> T2) -> Matrix<T2> {
-        let mut rows: Vec<Vec<T2>> = Vec::new();
-        for y in 0..self.height {
-            let mut columns: Vec<T2> = Vec::new();
-            for x in 0..self.width {
-                let val = self.get(x, y).unwrap();
-                columns.push(f(val));
-            }
-            rows.push(columns);
-        }
-
         Matrix {
-            width: rows[0].len(),
-            height: rows.len(),
-            data: rows,
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(f).collect(),
         }
     }
 
     pub fn map_xy<T2>(&self, mut f: impl FnMut(&T, usize, usize) -> T2) -> Matrix<T2> {
-        let mut rows: Vec<Vec<T2>> = Vec::new();
-        for y in 0..self.height {
-            let mut columns: Vec<T2> = Vec::new();
-            for x in 0..self.width {
-                let val = self.get(x, y).unwrap();
-                columns.push(f(val, x, y));
-            }
-            rows.push(columns);
-        }
-
+        let width = self.width;
         Matrix {
-            width: rows[0].len(),
-            height: rows.len(),
-            data: rows,
+            width: self.width,
+            height: self.height,
+            data: self.data.iter()
+                .enumerate()
+                .map(|(i, val)| f(val, i % width, i / width))
+                .collect(),
         }
     }
 
@@ -61,17 +106,7 @@ where
     where
         F: Fn(&T) -> bool,
     {
-        let mut count = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let val = self.get(x, y).unwrap();
-                if f(val) {
-                    count += 1;
-                }
-            }
-        }
-
-        count
+        self.data.iter().filter(|val| f(val)).count()
     }
 }
 
@@ -85,127 +120,552 @@ impl<T> Matrix<T> {
     }
 
     pub fn get(&self, x: usize, y: usize) -> Option<&T> {
-        self.data.get(y).and_then(|row| row.get(x))
+        if x < self.width && y < self.height {
+            self.data.get(y * self.width + x)
+        } else {
+            None
+        }
     }
 
     pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
-        self.data.get_mut(y).and_then(|row| row.get_mut(x))
+        if x < self.width && y < self.height {
+            self.data.get_mut(y * self.width + x)
+        } else {
+            None
+        }
     }
 
     pub fn set(&mut self, x: usize, y: usize, value: T) {
-        self.data[y][x] = value;
+        self[(x, y)] = value;
     }
-}
 
-impl<T> Matrix<T>
-where
-    T: Clone,
-{
-    pub fn new_fill(width: usize, height: usize, fill: T) -> Self {
-        Self {
-            width,
-            height,
-            data: vec![vec![fill; width]; height],
-        }
+    /// Returns all cells in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
     }
-}
 
-#[allow(dead_code)]
-impl<T> Matrix<T>
-where
-    T: Default + Clone + Eq + PartialEq,
-{
-    pub fn flood_eq<F>(&self, x: usize, y: usize, mut f: F) -> Matrix<bool>
-    where
-        F: FnMut(&usize, &usize),
-    {
-        let mut visit_mat: Matrix<bool> = Matrix::new(self.width, self.height);
-        let val = self.get(x, y).unwrap();
-        self.flood_eq_impl(x, y, val, &mut f, &mut visit_mat);
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    /// Moves one step from `position` in the given direction, or returns `None` when leaving the matrix.
+    pub fn step(&self, position: &Position, facing: Facing) -> Option<Position> {
+        self.offset(position.x, position.y, facing.offset())
+    }
+
+    /// Moves one step from `position` in the given direction, wrapping around the edges.
+    pub fn step_wrapping(&self, position: &Position, facing: Facing) -> Option<Position> {
+        self.offset_wrapping(position.x, position.y, facing.offset())
+    }
 
-        visit_mat
+    /// The in-bounds orthogonal neighbours of `(x, y)`, clockwise starting north.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbors_in(x, y, &Facing::ALL)
     }
 
-    fn flood_eq_impl<F>(&self, x: usize, y: usize, value: &T, f: &mut F, mut visit_mat: &mut Matrix<bool>)
+    /// The in-bounds orthogonal and diagonal neighbours of `(x, y)`, clockwise starting north.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (Position, &T)> {
+        OFFSETS_8.into_iter().filter_map(move |offset| self.cell_at(self.offset(x, y, offset)))
+    }
+
+    /// The in-bounds neighbours of `(x, y)` in the given directions, in the order of `facings`.
+    pub fn neighbors_in<'a>(&'a self, x: usize, y: usize, facings: &'a [Facing]) -> impl Iterator<Item = (Position, &'a T)> {
+        facings.iter().filter_map(move |facing| self.cell_at(self.offset(x, y, facing.offset())))
+    }
+
+    /// The in-bounds neighbours of `(x, y)` for the given connectivity, clockwise starting north.
+    pub fn neighbors(&self, x: usize, y: usize, connectivity: Connectivity) -> impl Iterator<Item = (Position, &T)> {
+        connectivity.offsets().iter().filter_map(move |offset| self.cell_at(self.offset(x, y, *offset)))
+    }
+
+    /// `grid::flood_where` from `(x, y)`, collecting the filled cells in a `BitMatrix`.
+    pub fn flood_where<F>(&self, x: usize, y: usize, connectivity: Connectivity, f: F) -> BitMatrix
     where
-        F: FnMut(&usize, &usize),
+        F: FnMut(&Position, &T, &Position, &T) -> bool,
     {
-        if let Some(visited) = visit_mat.get(x, y) {
-            if *visited {
-                return;
-            }
-        } else {
-            return;
+        let mut visited = BitMatrix::new(self.width, self.height);
+        grid::flood_into(self, Position::new(x, y), connectivity, &mut visited, f);
+        visited
+    }
+
+    /// The cells of row `y` from left to right.
+    pub fn row_cells(&self, y: usize) -> impl Iterator<Item = (Position, &T)> {
+        self.row(y).iter().enumerate().map(move |(x, cell)| (Position::new(x, y), cell))
+    }
+
+    /// The cells of column `x` from top to bottom.
+    pub fn column_cells(&self, x: usize) -> impl Iterator<Item = (Position, &T)> {
+        self.ray_offset(x, 0, (0, 1))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        (0..self.height).map(move |y| self.row_cells(y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        (0..self.width).map(move |x| self.column_cells(x))
+    }
+
+    /// Every diagonal running down and to the right, starting with the one from the top right
+    /// corner and ending with the one from the bottom left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        let top = (0..self.width).rev().map(|x| (x, 0));
+        let left = (1..self.height).filter(|_| self.width > 0).map(|y| (0, y));
+        top.chain(left).map(move |(x, y)| self.ray_offset(x, y, (1, 1)))
+    }
+
+    /// Every diagonal running down and to the left, starting with the one from the top left
+    /// corner and ending with the one from the bottom right corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        let top = (0..self.width).map(|x| (x, 0));
+        let right = (1..self.height).filter(|_| self.width > 0).map(|y| (self.width - 1, y));
+        top.chain(right).map(move |(x, y)| self.ray_offset(x, y, (-1, 1)))
+    }
+
+    /// The cells from `(x, y)` in the given direction up to the edge of the matrix, starting with
+    /// `(x, y)` itself. Use `skip(1)` to only look at what can be seen from the cell.
+    pub fn ray(&self, x: usize, y: usize, facing: Facing) -> impl Iterator<Item = (Position, &T)> {
+        self.ray_offset(x, y, facing.offset())
+    }
+
+    /// Like `ray`, but repeatedly steps by `offset`, which allows diagonal or knight-like rays.
+    /// The offset must not be `(0, 0)`, as the ray would never end.
+    pub fn ray_offset(&self, x: usize, y: usize, offset: (isize, isize)) -> impl Iterator<Item = (Position, &T)> {
+        let start = Position::new(x, y);
+        std::iter::successors(Some(start).filter(|start| self.contains(start)), move |position| self.offset(position.x, position.y, offset))
+            .map(move |position| (position, &self[position]))
+    }
+
+    /// Every `k` x `k` window in row-major order, together with the position of its top left cell.
+    /// Panics for `k == 0`, like `slice::windows`.
+    pub fn windows(&self, k: usize) -> impl Iterator<Item = (Position, SubMatrix<'_, T>)> {
+        if k == 0 {
+            panic!("Window size must be non-zero");
         }
 
-        if *self.get(x, y).unwrap() != *value {
-            return;
+        let columns = (self.width + 1).saturating_sub(k);
+        let rows = (self.height + 1).saturating_sub(k);
+        (0..rows)
+            .flat_map(move |y| (0..columns).map(move |x| Position::new(x, y)))
+            .map(move |position| (position, self.sub_matrix(position.x, position.y, k, k)))
+    }
+
+    /// Like `neighbors4`, but treats the matrix as a torus. On matrices narrower or shorter than
+    /// three cells the same neighbour can be returned more than once.
+    pub fn neighbors4_wrapping(&self, x: usize, y: usize) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbors_in_wrapping(x, y, &Facing::ALL)
+    }
+
+    pub fn neighbors8_wrapping(&self, x: usize, y: usize) -> impl Iterator<Item = (Position, &T)> {
+        OFFSETS_8.into_iter().filter_map(move |offset| self.cell_at(self.offset_wrapping(x, y, offset)))
+    }
+
+    pub fn neighbors_in_wrapping<'a>(&'a self, x: usize, y: usize, facings: &'a [Facing]) -> impl Iterator<Item = (Position, &'a T)> {
+        facings.iter().filter_map(move |facing| self.cell_at(self.offset_wrapping(x, y, facing.offset())))
+    }
+
+    fn offset(&self, x: usize, y: usize, (dx, dy): (isize, isize)) -> Option<Position> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        let position = Position::new(x, y);
+        if self.contains(&position) { Some(position) } else { None }
+    }
+
+    fn offset_wrapping(&self, x: usize, y: usize, (dx, dy): (isize, isize)) -> Option<Position> {
+        if self.width == 0 || self.height == 0 {
+            return None;
         }
 
-        f(&x, &y);
-        visit_mat.set(x, y, true);
+        let x = (x as isize + dx).rem_euclid(self.width as isize) as usize;
+        let y = (y as isize + dy).rem_euclid(self.height as isize) as usize;
+        Some(Position::new(x, y))
+    }
+
+    fn cell_at(&self, position: Option<Position>) -> Option<(Position, &T)> {
+        let position = position?;
+        self.get(position.x, position.y).map(|cell| (position, cell))
+    }
 
-        if x > 0 {
-            self.flood_eq_impl(x - 1, y, value, f, &mut visit_mat);
+    /// A view of the `width` x `height` rectangle starting at `(x, y)`, without copying any cells.
+    pub fn sub_matrix(&self, x: usize, y: usize, width: usize, height: usize) -> SubMatrix<'_, T> {
+        if x + width > self.width || y + height > self.height {
+            panic!("Sub matrix {width}x{height} at ({x}, {y}) doesn't fit in the {}x{} matrix", self.width, self.height);
         }
-        if y > 0 {
-            self.flood_eq_impl(x, y - 1, value, f, &mut visit_mat);
+
+        SubMatrix { matrix: self, x, y, width, height }
+    }
+
+    /// Inserts `row` before row `y`, or appends it when `y` equals the height. A 0x0 matrix takes
+    /// its width from the row, a matrix that only lost its rows keeps its width.
+    pub fn insert_row(&mut self, y: usize, row: Vec<T>) {
+        if self.width == 0 && self.height == 0 {
+            self.width = row.len();
         }
-        if x < self.width - 1 {
-            self.flood_eq_impl(x + 1, y, value, f, &mut visit_mat);
+        if row.len() != self.width || y > self.height {
+            panic!("Can't insert a row of {} cells at {y} in the {}x{} matrix", row.len(), self.width, self.height);
         }
-        if y < self.height - 1 {
-            self.flood_eq_impl(x, y + 1, value, f, &mut visit_mat);
+
+        let start = y * self.width;
+        self.data.splice(start..start, row);
+        self.height += 1;
+    }
+
+    pub fn remove_row(&mut self, y: usize) -> Vec<T> {
+        if y >= self.height {
+            panic!("Can't remove row {y} from the {}x{} matrix", self.width, self.height);
         }
+
+        self.height -= 1;
+        self.data.drain(y * self.width..(y + 1) * self.width).collect()
     }
 
-    pub fn flood_where<F>(&self, x: usize, y: usize, mut f: F) -> Matrix<bool>
-    where
-        F: FnMut(&usize, &usize, &T) -> bool,
-    {
-        let mut visit_mat: Matrix<bool> = Matrix::new(self.width, self.height);
-        let val = self.get(x, y).unwrap();
-        self.flood_where_impl(x, y, val, &mut f, &mut visit_mat);
+    /// Inserts `column` before column `x`, or appends it when `x` equals the width. A 0x0 matrix
+    /// takes its height from the column, a matrix that only lost its columns keeps its height.
+    pub fn insert_column(&mut self, x: usize, column: Vec<T>) {
+        if self.width == 0 && self.height == 0 {
+            self.height = column.len();
+        }
+        if column.len() != self.height || x > self.width {
+            panic!("Can't insert a column of {} cells at {x} in the {}x{} matrix", column.len(), self.width, self.height);
+        }
+
+        let mut cells = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity((self.width + 1) * self.height);
+        for cell in column {
+            data.extend(cells.by_ref().take(x));
+            data.push(cell);
+            data.extend(cells.by_ref().take(self.width - x));
+        }
 
-        visit_mat
+        self.data = data;
+        self.width += 1;
     }
 
-    fn flood_where_impl<F>(&self, x: usize, y: usize, value: &T, f: &mut F, mut visit_mat: &mut Matrix<bool>)
-    where
-        F: FnMut(&usize, &usize, &T) -> bool,
-    {
-        if let Some(visited) = visit_mat.get(x, y) {
-            if *visited {
-                return;
-            }
-        } else {
-            return;
+    pub fn remove_column(&mut self, x: usize) -> Vec<T> {
+        if x >= self.width {
+            panic!("Can't remove column {x} from the {}x{} matrix", self.width, self.height);
         }
 
-        if *self.get(x, y).unwrap() != *value {
-            return;
+        let mut cells = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity((self.width - 1) * self.height);
+        let mut column = Vec::with_capacity(self.height);
+        for _ in 0..self.height {
+            data.extend(cells.by_ref().take(x));
+            column.extend(cells.next());
+            data.extend(cells.by_ref().take(self.width - x - 1));
         }
 
-        let value = self.get(x, y).unwrap();
-        if !f(&x, &y, value) {
-            return;
+        self.data = data;
+        self.width -= 1;
+        column
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> usize {
+        if x >= self.width || y >= self.height {
+            panic!("Position ({x}, {y}) is outside of the {}x{} matrix", self.width, self.height);
         }
-        visit_mat.set(x, y, true);
 
-        if x > 0 {
-            self.flood_where_impl(x - 1, y, value, f, &mut visit_mat);
+        y * self.width + x
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: PartialEq,
+{
+    /// Every cell that differs between the matrices. When their sizes differ, cells that only
+    /// exist in one of them are listed with `None` for the other.
+    pub fn diff<'a>(&'a self, other: &'a Matrix<T>) -> Vec<CellChange<'a, T>> {
+        let width = self.width.max(other.width);
+        let height = self.height.max(other.height);
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| Position::new(x, y)))
+            .filter_map(|position| {
+                let left = self.get(position.x, position.y);
+                let right = other.get(position.x, position.y);
+                if left == right { None } else { Some(CellChange { position, left, right }) }
+            })
+            .collect()
+    }
+
+    /// Labels every region of equal cells and measures its area, perimeter and number of sides.
+    /// Perimeter and sides are always measured along orthogonal edges, also for `Connectivity::Eight`.
+    pub fn components(&self, connectivity: Connectivity) -> Components {
+        let mut labels = Matrix::new_fill(self.width, self.height, u32::MAX);
+        let mut regions = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if labels[(x, y)] != u32::MAX {
+                    continue;
+                }
+
+                let label = regions.len() as u32;
+                let start = Position::new(x, y);
+                labels[start] = label;
+                let mut area = 0;
+                let mut queue = VecDeque::from([start]);
+                while let Some(from) = queue.pop_front() {
+                    area += 1;
+                    for (to, cell) in self.neighbors(from.x, from.y, connectivity) {
+                        if labels[to] == u32::MAX && *cell == self[from] {
+                            labels[to] = label;
+                            queue.push_back(to);
+                        }
+                    }
+                }
+
+                regions.push(Region { label, start, area, perimeter: 0, sides: 0 });
+            }
         }
-        if y > 0 {
-            self.flood_where_impl(x, y - 1, value, f, &mut visit_mat);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let position = Position::new(x, y);
+                let label = labels[position];
+                let same = |position: Option<Position>| position.is_some_and(|position| labels[position] == label);
+
+                for facing in Facing::ALL {
+                    if same(labels.step(&position, facing)) {
+                        continue;
+                    }
+                    regions[label as usize].perimeter += 1;
+
+                    // A fence only starts a new side if the cell before it along the fence
+                    // doesn't carry the same fence.
+                    let along = match facing {
+                        Facing::North | Facing::South => Facing::West,
+                        Facing::East | Facing::West => Facing::North,
+                    };
+                    let previous = labels.step(&position, along);
+                    let continues = same(previous) && !same(previous.and_then(|previous| labels.step(&previous, facing)));
+                    if !continues {
+                        regions[label as usize].sides += 1;
+                    }
+                }
+            }
         }
-        if x < self.width - 1 {
-            self.flood_where_impl(x + 1, y, value, f, &mut visit_mat);
+
+        Components { labels, regions }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Hash,
+{
+    /// A 64-bit hash of the dimensions and all cells, which is the same on every run of the
+    /// program, for cheaply deduplicating states. Equal fingerprints don't guarantee equal matrices.
+    pub fn fingerprint(&self) -> u64 {
+        ahash::RandomState::with_seeds(0x243F_6A88_85A3_08D3, 0x1319_8A2E_0370_7344, 0xA409_3822_299F_31D0, 0x082E_FA98_EC4E_6C89)
+            .hash_one(self)
+    }
+}
+
+/// Like `assert_eq!` for matrices, but shows both grids side by side with the differing cells
+/// marked when they aren't equal.
+#[cfg(test)]
+macro_rules! assert_matrix_eq {
+    ($left:expr, $right:expr) => {
+        let (left, right) = (&$left, &$right);
+        if left != right {
+            panic!("Matrices differ in {} cells\n{}", left.diff(right).len(), left.render_diff(right));
         }
-        if y < self.height - 1 {
-            self.flood_where_impl(x, y + 1, value, f, &mut visit_mat);
+    };
+}
+
+#[cfg(test)]
+pub(crate) use assert_matrix_eq;
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        &self.data[self.index_of(x, y)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        let index = self.index_of(x, y);
+        &mut self.data[index]
+    }
+}
+
+impl<T> Index<Position> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        &self[(position.x, position.y)]
+    }
+}
+
+impl<T> IndexMut<Position> for Matrix<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        &mut self[(position.x, position.y)]
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone,
+{
+    pub fn new_fill(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![fill; width * height],
         }
     }
 
+    /// Swaps rows and columns, the cell at `(x, y)` ends up at `(y, x)`.
+    pub fn transpose(&self) -> Matrix<T> {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Rotates a quarter turn clockwise, the first row becomes the last column.
+    pub fn rotate_cw(&self) -> Matrix<T> {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// Rotates a quarter turn counter-clockwise, the first row becomes the first column upside down.
+    pub fn rotate_ccw(&self) -> Matrix<T> {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    pub fn rotate_180(&self) -> Matrix<T> {
+        let (width, height) = (self.width, self.height);
+        self.remap(width, height, |x, y| (width - 1 - x, height - 1 - y))
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Matrix<T> {
+        let width = self.width;
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Matrix<T> {
+        let height = self.height;
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    /// Repeats the matrix `nx` times horizontally and `ny` times vertically.
+    pub fn tile(&self, nx: usize, ny: usize) -> Matrix<T> {
+        let (width, height) = (self.width, self.height);
+        self.remap(width * nx, height * ny, |x, y| (x % width, y % height))
+    }
+
+    /// Builds a `width` x `height` matrix where every cell is copied from the position returned by `source`.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> (usize, usize)) -> Matrix<T> {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (source_x, source_y) = source(x, y);
+                self.data[source_y * self.width + source_x].clone()
+            })
+            .collect();
+
+        Matrix { width, height, data }
+    }
+}
+
+/// A read-only view of a rectangle inside a matrix, created by `Matrix::sub_matrix`.
+pub struct SubMatrix<'a, T> {
+    matrix: &'a Matrix<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Clone for SubMatrix<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SubMatrix<'_, T> {}
+
+impl<'a, T> SubMatrix<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The position of the top left cell of the view in the underlying matrix.
+    pub fn origin(&self) -> Position {
+        Position::new(self.x, self.y)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x < self.width && y < self.height {
+            self.matrix.get(self.x + x, self.y + y)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        &self.matrix.row(self.y + y)[self.x..self.x + self.width]
+    }
+
+    pub fn to_matrix(self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        Matrix {
+            width: self.width,
+            height: self.height,
+            data: (0..self.height).flat_map(|y| self.row(y).iter().cloned()).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for SubMatrix<'_, T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(x, y).unwrap_or_else(|| panic!("Position ({x}, {y}) is outside of the {}x{} sub matrix", self.width, self.height))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Default + Clone + Eq + PartialEq,
+{
+    /// Fills the region of cells equal to the one at `(x, y)`, calling `f` for every filled cell.
+    pub fn flood_eq<F>(&self, x: usize, y: usize, connectivity: Connectivity, mut f: F) -> BitMatrix
+    where
+        F: FnMut(&usize, &usize),
+    {
+        f(&x, &y);
+        self.flood_where(x, y, connectivity, |_, from, to_position, to| {
+            if from != to {
+                return false;
+            }
+            f(&to_position.x, &to_position.y);
+            true
+        })
+    }
+
     pub fn each<F>(&self, mut f: F)
     where
         F: FnMut(&usize, &usize, &T),
@@ -236,54 +696,599 @@ where
     }
 }
 
+/// Why a grid couldn't be parsed. Lines and columns are 1-based and count from the start of the
+/// whole text, columns count numbers instead of characters for whitespace separated grids.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MatrixParseError<E> {
+    Ragged { line: usize, width: usize, expected: usize },
+    Cell { line: usize, column: usize, error: E },
+}
+
+impl<E: Display> Display for MatrixParseError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatrixParseError::Ragged { line, width, expected } => write!(f, "line {}: row has {} cells, expected {}", line, width, expected),
+            MatrixParseError::Cell { line, column, error } => write!(f, "line {}, column {}: {}", line, column, error),
+        }
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Parses one cell per character, `f` receives the character and its position in the grid.
+    /// Trailing blank lines are ignored, an empty text gives an empty matrix.
+    pub fn from_text_with<E>(text: &str, f: impl FnMut(char, usize, usize) -> Result<T, E>) -> Result<Self, MatrixParseError<E>> {
+        let lines = text.trim_end_matches(['\r', '\n']).lines().enumerate();
+        Self::parse_rows(lines.map(|(index, line)| (index + 1, line.chars())), f)
+    }
+
+    /// Parses every grid of a text where grids are separated by blank lines.
+    pub fn grids_from_text_with<E>(text: &str, mut f: impl FnMut(char, usize, usize) -> Result<T, E>) -> Result<Vec<Self>, MatrixParseError<E>> {
+        let mut grids = Vec::new();
+        let mut lines = text.lines().enumerate().peekable();
+        while lines.peek().is_some() {
+            let grid = lines.by_ref()
+                .skip_while(|(_, line)| line.trim().is_empty())
+                .take_while(|(_, line)| !line.trim().is_empty())
+                .map(|(index, line)| (index + 1, line.chars()));
+            let matrix = Self::parse_rows(grid, &mut f)?;
+            if matrix.height > 0 {
+                grids.push(matrix);
+            }
+        }
+
+        Ok(grids)
+    }
+
+    fn parse_rows<I, E>(lines: impl Iterator<Item = (usize, I)>, mut f: impl FnMut(I::Item, usize, usize) -> Result<T, E>) -> Result<Self, MatrixParseError<E>>
+    where
+        I: Iterator,
+    {
+        let mut data = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for (line, cells) in lines {
+            let row_start = data.len();
+            for (x, cell) in cells.enumerate() {
+                let cell = f(cell, x, height).map_err(|error| MatrixParseError::Cell { line, column: x + 1, error })?;
+                data.push(cell);
+            }
+
+            let row_width = data.len() - row_start;
+            if height == 0 {
+                width = row_width;
+            } else if row_width != width {
+                return Err(MatrixParseError::Ragged { line, width: row_width, expected: width });
+            }
+            height += 1;
+        }
+
+        Ok(Self { width, height, data })
+    }
+}
+
 impl<T> Matrix<T>
 where
-    T: Default + Clone + From<char>,
+    T: From<char>,
 {
+    /// Parses one cell per character, panicking on ragged input.
     pub fn from_text(text: &str) -> Self {
-        let mut rows: Vec<Vec<T>> = Vec::new();
-        for line in text.lines() {
-            let mut columns: Vec<T> = Vec::new();
-            for ch in line.chars() {
-                columns.push(ch.into());
+        Self::from_text_with(text, |ch, _, _| Ok::<T, Infallible>(ch.into()))
+            .unwrap_or_else(|error| panic!("Invalid grid, {}", error))
+    }
+
+    /// Like `from_text`, but also returns the positions of every occurrence of the `markers`,
+    /// e.g. the start `S` and end `E` of a maze. The marked cells are parsed like any other.
+    pub fn from_text_marked(text: &str, markers: &[char]) -> (Self, HashMap<char, Vec<Position>>) {
+        let mut marks: HashMap<char, Vec<Position>> = HashMap::new();
+        let matrix = Self::from_text_with(text, |ch, x, y| {
+            if markers.contains(&ch) {
+                marks.entry(ch).or_default().push(Position::new(x, y));
             }
-            rows.push(columns);
-        }
+            Ok::<T, Infallible>(ch.into())
+        }).unwrap_or_else(|error| panic!("Invalid grid, {}", error));
 
-        Self {
-            width: rows[0].len(),
-            height: rows.len(),
-            data: rows,
-        }
+        (matrix, marks)
+    }
+
+    /// Parses every grid of a text where grids are separated by blank lines, panicking on ragged input.
+    pub fn grids_from_text(text: &str) -> Vec<Self> {
+        Self::grids_from_text_with(text, |ch, _, _| Ok::<T, Infallible>(ch.into()))
+            .unwrap_or_else(|error| panic!("Invalid grid, {}", error))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: From<u8>,
+{
+    /// Parses a grid of single digits like `0123`.
+    pub fn from_digits(text: &str) -> Result<Self, MatrixParseError<String>> {
+        Self::from_text_with(text, |ch, _, _| {
+            ch.to_digit(10)
+                .map(|digit| T::from(digit as u8))
+                .ok_or_else(|| format!("'{}' is not a digit", ch))
+        })
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: FromStr,
+{
+    /// Parses a grid of whitespace separated values like `12 -3 7`.
+    pub fn from_numbers(text: &str) -> Result<Self, MatrixParseError<T::Err>> {
+        let lines = text.trim_end().lines().enumerate();
+        Self::parse_rows(lines.map(|(index, line)| (index + 1, line.split_whitespace())), |number, _, _| number.parse())
     }
 }
 
 impl<T> Debug for Matrix<T>
 where
-    T: Debug + Clone + Default + Display,
+    T: Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut data_hash_map: HashMap<(usize, usize), String> = HashMap::new();
-        let mut max_string_length = 0;
-        for x in 0..self.width {
-            for y in 0..self.height {
-                let cell = self.get(x, y).unwrap();
-                let cell_string = format!("{}", cell);
-                max_string_length = max_string_length.max(cell_string.len());
-                data_hash_map.insert((x, y), cell_string);
-            }
-        }
+        write!(f, "{}", self.render().plain())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let string = data_hash_map.get(&(x, y)).unwrap();
-                write!(f, "{:width$}", string, width = max_string_length + 1)?;
-            }
-            if y < self.height - 1 {
-                writeln!(f)?;
-            }
+    /// A 3x2 matrix holding `10 * y + x` in every cell.
+    fn numbered() -> Matrix<usize> {
+        Matrix::<u8>::new(3, 2).map_xy(|_, x, y| 10 * y + x)
+    }
+
+    #[test]
+    fn stores_cells_row_major() {
+        let matrix = numbered();
+        assert_eq!((matrix.width(), matrix.height()), (3, 2));
+        assert_eq!(matrix.as_slice(), [0, 1, 2, 10, 11, 12]);
+        assert_eq!(matrix.row(1), [10, 11, 12]);
+        assert_eq!(matrix[(2, 1)], 12);
+        assert_eq!(matrix[Position::new(1, 0)], 1);
+        assert_eq!(matrix.map(|cell| cell % 2).count(|cell| *cell == 0), 4);
+    }
+
+    #[test]
+    fn get_checks_both_coordinates() {
+        let mut matrix = numbered();
+        assert_eq!(matrix.get(2, 1), Some(&12));
+        assert_eq!(matrix.get(3, 0), None);
+        assert_eq!(matrix.get(0, 2), None);
+        assert_eq!(matrix.get(0, 0usize.wrapping_sub(1)), None);
+        assert_eq!(matrix.get(0usize.wrapping_sub(1), 0), None);
+        assert_eq!(matrix.get_mut(1, 0usize.wrapping_sub(1)), None);
+        assert_eq!(matrix.get_mut(3, 0), None);
+        assert_eq!(Matrix::<u8>::default().get(0, 0), None);
+    }
+
+    #[test]
+    fn writes_cells() {
+        let mut matrix = numbered();
+        matrix.set(0, 1, 99);
+        *matrix.get_mut(2, 0).unwrap() = 98;
+        matrix[(1, 1)] = 97;
+        matrix.row_mut(0)[0] = 96;
+        matrix.as_mut_slice()[1] = 95;
+        assert_eq!(matrix.as_slice(), [96, 95, 98, 99, 97, 12]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Position (3, 0) is outside of the 3x2 matrix")]
+    fn index_outside_of_the_row() {
+        _ = numbered()[(3, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Position (0, 2) is outside of the 3x2 matrix")]
+    fn set_outside_of_the_matrix() {
+        numbered().set(0, 2, 1);
+    }
+
+    #[test]
+    fn contains_positions() {
+        let matrix = numbered();
+        assert!(matrix.contains(&Position::new(2, 1)));
+        assert!(!matrix.contains(&Position::new(3, 1)));
+        assert!(!matrix.contains(&Position::new(0, 2)));
+    }
+
+    #[test]
+    fn visits_every_cell() {
+        let matrix = numbered();
+        let mut cells = Vec::new();
+        matrix.each(|x, y, cell| cells.push((*x, *y, *cell)));
+        assert_eq!(cells, [(0, 0, 0), (1, 0, 1), (2, 0, 2), (0, 1, 10), (1, 1, 11), (2, 1, 12)]);
+        assert_eq!(matrix.positions(|_, _, cell| cell % 10 == 2), HashSet::from([(2, 0), (2, 1)]));
+    }
+
+    fn positions<'a, T: 'a>(cells: impl Iterator<Item = (Position, &'a T)>) -> Vec<(usize, usize)> {
+        cells.map(|(position, _)| (position.x, position.y)).collect()
+    }
+
+    #[test]
+    fn neighbors_stay_inside() {
+        let matrix = numbered();
+        assert_eq!(positions(matrix.neighbors4(1, 0)), [(2, 0), (1, 1), (0, 0)]);
+        assert_eq!(positions(matrix.neighbors8(0, 1)), [(0, 0), (1, 0), (1, 1)]);
+        assert_eq!(positions(matrix.neighbors8(1, 1)), [(1, 0), (2, 0), (2, 1), (0, 1), (0, 0)]);
+        assert_eq!(positions(matrix.neighbors(2, 1, Connectivity::Four)), [(2, 0), (1, 1)]);
+        assert_eq!(positions(matrix.neighbors(2, 1, Connectivity::Eight)), [(2, 0), (1, 1), (1, 0)]);
+        assert_eq!(matrix.neighbors4(1, 0).map(|(_, cell)| *cell).collect::<Vec<_>>(), [2, 11, 0]);
+    }
+
+    #[test]
+    fn neighbors_in_follows_the_facings() {
+        let matrix = numbered();
+        assert_eq!(positions(matrix.neighbors_in(1, 1, &[Facing::West, Facing::South, Facing::North])), [(0, 1), (1, 0)]);
+        assert_eq!(positions(matrix.neighbors_in(0, 0, &[])), []);
+    }
+
+    #[test]
+    fn neighbors_wrap_around() {
+        let matrix = numbered();
+        assert_eq!(positions(matrix.neighbors4_wrapping(0, 0)), [(0, 1), (1, 0), (0, 1), (2, 0)]);
+        assert_eq!(positions(matrix.neighbors8_wrapping(2, 1)), [(2, 0), (0, 0), (0, 1), (0, 0), (2, 0), (1, 0), (1, 1), (1, 0)]);
+        assert_eq!(positions(matrix.neighbors_in_wrapping(0, 0, &[Facing::West])), [(2, 0)]);
+        assert_eq!(positions(Matrix::<u8>::default().neighbors4_wrapping(0, 0)), []);
+    }
+
+    #[test]
+    fn steps() {
+        let matrix = numbered();
+        assert_eq!(matrix.step(&Position::new(0, 0), Facing::East), Some(Position::new(1, 0)));
+        assert_eq!(matrix.step(&Position::new(0, 0), Facing::North), None);
+        assert_eq!(matrix.step(&Position::new(2, 1), Facing::East), None);
+        assert_eq!(matrix.step_wrapping(&Position::new(0, 0), Facing::North), Some(Position::new(0, 1)));
+        assert_eq!(matrix.step_wrapping(&Position::new(2, 1), Facing::East), Some(Position::new(0, 1)));
+    }
+
+    const GARDEN: &str = "AAAA\nBBCD\nBBCC\nEEEC\n";
+
+    #[test]
+    fn flood_where_follows_the_predicate() {
+        let matrix = Matrix::<u8>::from_digits("0123\n1234\n2345\n").unwrap();
+        let uphill = matrix.flood_where(0, 0, Connectivity::Four, |_, from, _, to| *to == from + 1);
+        assert_eq!(uphill.count_ones(), 12);
+
+        let small = matrix.flood_where(0, 0, Connectivity::Four, |_, _, _, to| *to < 2);
+        assert_eq!(small.positions().collect::<Vec<_>>(), [Position::new(0, 0), Position::new(1, 0), Position::new(0, 1)]);
+
+        let mut steps = Vec::new();
+        matrix.flood_where(3, 2, Connectivity::Eight, |from, _, to, _| {
+            steps.push((*from, *to));
+            false
+        });
+        assert_eq!(steps, [(Position::new(3, 2), Position::new(3, 1)), (Position::new(3, 2), Position::new(2, 2)),
+                           (Position::new(3, 2), Position::new(2, 1))]);
+    }
+
+    #[test]
+    fn flood_eq_reports_every_filled_cell() {
+        let matrix = Matrix::<char>::from_text(GARDEN);
+        let mut filled = Vec::new();
+        let region = matrix.flood_eq(2, 1, Connectivity::Four, |x, y| filled.push((*x, *y)));
+        assert_eq!(filled, [(2, 1), (2, 2), (3, 2), (3, 3)]);
+        assert_eq!(region.count_ones(), 4);
+
+        assert_eq!(matrix.flood_eq(3, 1, Connectivity::Four, |_, _| {}).count_ones(), 1);
+        assert_eq!(matrix.flood_eq(3, 1, Connectivity::Eight, |_, _| {}).count_ones(), 1);
+        assert_eq!(matrix.flood_eq(0, 3, Connectivity::Eight, |_, _| {}).count_ones(), 3);
+    }
+
+    #[test]
+    fn flood_fills_large_regions() {
+        let matrix = Matrix::new_fill(1000, 1000, 0u8);
+        assert_eq!(matrix.flood_eq(500, 500, Connectivity::Four, |_, _| {}).count_ones(), 1_000_000);
+    }
+
+    #[test]
+    fn components_measure_regions() {
+        let components = Matrix::<char>::from_text(GARDEN).components(Connectivity::Four);
+        let measures = components.regions.iter()
+            .map(|region| (region.start, region.area, region.perimeter, region.sides))
+            .collect::<Vec<_>>();
+        assert_eq!(measures, [
+            (Position::new(0, 0), 4, 10, 4),
+            (Position::new(0, 1), 4, 8, 4),
+            (Position::new(2, 1), 4, 10, 8),
+            (Position::new(3, 1), 1, 4, 4),
+            (Position::new(0, 3), 3, 8, 4),
+        ]);
+        assert_eq!(components.labels.row(2), [1, 1, 2, 2]);
+        assert!(components.regions.iter().enumerate().all(|(index, region)| region.label == index as u32));
+    }
+
+    #[test]
+    fn components_count_the_sides_of_holes() {
+        let components = Matrix::<char>::from_text("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA\n").components(Connectivity::Four);
+        assert_eq!(components.regions.iter().map(|region| (region.area, region.sides)).collect::<Vec<_>>(), [(28, 12), (4, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn components_with_diagonals() {
+        let components = Matrix::<char>::from_text("X.X\n.X.\n").components(Connectivity::Eight);
+        let regions = components.regions.iter().map(|region| (region.area, region.perimeter)).collect::<Vec<_>>();
+        assert_eq!(regions, [(3, 12), (3, 12)]);
+        assert_eq!(components.labels.row(1), [1, 0, 1]);
+    }
+
+    #[test]
+    fn transforms() {
+        let matrix = numbered();
+        assert_eq!(matrix.transpose().as_slice(), [0, 10, 1, 11, 2, 12]);
+        assert_eq!(matrix.rotate_cw().as_slice(), [10, 0, 11, 1, 12, 2]);
+        assert_eq!(matrix.rotate_ccw().as_slice(), [2, 12, 1, 11, 0, 10]);
+        assert_eq!(matrix.rotate_180().as_slice(), [12, 11, 10, 2, 1, 0]);
+        assert_eq!(matrix.flip_horizontal().as_slice(), [2, 1, 0, 12, 11, 10]);
+        assert_eq!(matrix.flip_vertical().as_slice(), [10, 11, 12, 0, 1, 2]);
+        assert_eq!((matrix.rotate_cw().width(), matrix.rotate_cw().height()), (2, 3));
+        assert_matrix_eq!(matrix.rotate_cw().rotate_ccw(), matrix);
+        assert_matrix_eq!(matrix.rotate_cw().rotate_cw(), matrix.rotate_180());
+        assert_matrix_eq!(matrix.transpose().transpose(), matrix);
+
+        let tiled = matrix.tile(2, 3);
+        assert_eq!((tiled.width(), tiled.height()), (6, 6));
+        assert_eq!(tiled.row(5), [10, 11, 12, 10, 11, 12]);
+        assert_eq!(matrix.tile(0, 2).as_slice(), []);
+    }
+
+    #[test]
+    fn sub_matrix_views() {
+        let matrix = numbered();
+        let view = matrix.sub_matrix(1, 0, 2, 2);
+        assert_eq!((view.width(), view.height(), view.origin()), (2, 2, Position::new(1, 0)));
+        assert_eq!((view[(0, 1)], view.get(1, 0), view.get(2, 0)), (11, Some(&2), None));
+        assert_eq!(view.row(1), [11, 12]);
+        assert_eq!(view.to_matrix().as_slice(), [1, 2, 11, 12]);
+
+        let windows = matrix.windows(2).map(|(position, window)| (position, window.to_matrix().as_slice().to_vec())).collect::<Vec<_>>();
+        assert_eq!(windows, [(Position::new(0, 0), vec![0, 1, 10, 11]), (Position::new(1, 0), vec![1, 2, 11, 12])]);
+        assert_eq!(matrix.windows(3).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sub matrix 2x2 at (2, 0) doesn't fit in the 3x2 matrix")]
+    fn sub_matrix_outside() {
+        numbered().sub_matrix(2, 0, 2, 2);
+    }
+
+    #[test]
+    fn single_cell_windows_cover_the_matrix() {
+        let matrix = numbered();
+        assert!(matrix.windows(1).all(|(position, window)| matrix.contains(&position) && window[(0, 0)] == matrix[position]));
+        assert_eq!(matrix.windows(1).count(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "Window size must be non-zero")]
+    fn empty_windows_panic() {
+        let _ = numbered().windows(0);
+    }
+
+    #[test]
+    fn edits_rows_and_columns() {
+        let mut matrix = numbered();
+        matrix.insert_row(1, vec![5, 6, 7]);
+        matrix.insert_column(3, vec![8, 9, 4]);
+        assert_eq!((matrix.width(), matrix.height()), (4, 3));
+        assert_eq!(matrix.as_slice(), [0, 1, 2, 8, 5, 6, 7, 9, 10, 11, 12, 4]);
+
+        assert_eq!(matrix.remove_column(0), [0, 5, 10]);
+        assert_eq!(matrix.remove_row(2), [11, 12, 4]);
+        assert_eq!(matrix.as_slice(), [1, 2, 8, 6, 7, 9]);
+    }
+
+    #[test]
+    fn empty_matrices_take_the_size_of_the_first_line() {
+        let mut matrix = Matrix::<u8>::default();
+        matrix.insert_row(0, vec![1, 2, 3]);
+        assert_eq!((matrix.width(), matrix.height()), (3, 1));
+
+        let mut matrix = Matrix::<u8>::default();
+        matrix.insert_column(0, vec![1, 2]);
+        assert_eq!((matrix.width(), matrix.height()), (1, 2));
+    }
+
+    #[test]
+    fn emptied_matrices_keep_their_size() {
+        let mut matrix = numbered();
+        matrix.remove_column(0);
+        matrix.remove_column(0);
+        matrix.remove_column(0);
+        assert_eq!((matrix.width(), matrix.height()), (0, 2));
+        matrix.insert_column(0, vec![4, 5]);
+        assert_eq!(matrix.as_slice(), [4, 5]);
+
+        let mut matrix = numbered();
+        matrix.remove_row(0);
+        matrix.remove_row(0);
+        assert_eq!((matrix.width(), matrix.height()), (3, 0));
+        matrix.insert_row(0, vec![4, 5, 6]);
+        assert_eq!(matrix.as_slice(), [4, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't insert a column of 3 cells at 0 in the 0x2 matrix")]
+    fn column_longer_than_the_height_of_an_emptied_matrix() {
+        let mut matrix = Matrix::<u8>::new(0, 2);
+        matrix.insert_column(0, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't insert a row of 2 cells at 0 in the 3x0 matrix")]
+    fn row_shorter_than_the_width_of_an_emptied_matrix() {
+        let mut matrix = Matrix::<u8>::new(3, 0);
+        matrix.insert_row(0, vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't insert a row of 3 cells at 3 in the 3x2 matrix")]
+    fn row_past_the_end() {
+        numbered().insert_row(3, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't remove column 3 from the 3x2 matrix")]
+    fn remove_missing_column() {
+        numbered().remove_column(3);
+    }
+
+    fn values<'a>(cells: impl Iterator<Item = (Position, &'a usize)>) -> Vec<usize> {
+        cells.map(|(_, cell)| *cell).collect()
+    }
+
+    #[test]
+    fn iterates_rows_and_columns() {
+        let matrix = numbered();
+        assert_eq!(positions(matrix.row_cells(1)), [(0, 1), (1, 1), (2, 1)]);
+        assert_eq!(values(matrix.column_cells(2)), [2, 12]);
+        assert_eq!(matrix.rows().map(values).collect::<Vec<_>>(), [vec![0, 1, 2], vec![10, 11, 12]]);
+        assert_eq!(matrix.columns().map(values).collect::<Vec<_>>(), [vec![0, 10], vec![1, 11], vec![2, 12]]);
+    }
+
+    #[test]
+    fn iterates_diagonals() {
+        let matrix = numbered();
+        assert_eq!(matrix.diagonals().map(values).collect::<Vec<_>>(), [vec![2], vec![1, 12], vec![0, 11], vec![10]]);
+        assert_eq!(matrix.anti_diagonals().map(values).collect::<Vec<_>>(), [vec![0], vec![1, 10], vec![2, 11], vec![12]]);
+        assert_eq!(Matrix::<u8>::new(0, 3).diagonals().count(), 0);
+        assert_eq!(Matrix::<u8>::new(0, 3).anti_diagonals().count(), 0);
+    }
+
+    #[test]
+    fn casts_rays() {
+        let matrix = numbered();
+        assert_eq!(values(matrix.ray(0, 1, Facing::East)), [10, 11, 12]);
+        assert_eq!(values(matrix.ray(2, 1, Facing::North).skip(1)), [2]);
+        assert_eq!(values(matrix.ray(0, 0, Facing::West)), [0]);
+        assert_eq!(values(matrix.ray(3, 0, Facing::West)), []);
+        assert_eq!(values(matrix.ray_offset(0, 0, (2, 1))), [0, 12]);
+        assert_eq!(values(matrix.ray_offset(2, 0, (-1, 1))), [2, 11]);
+    }
+
+    #[test]
+    fn parses_text() {
+        let matrix = Matrix::<char>::from_text("ab\ncd\n\n\n");
+        assert_eq!((matrix.width(), matrix.height()), (2, 2));
+        assert_eq!(matrix.as_slice(), ['a', 'b', 'c', 'd']);
+        assert_eq!(Matrix::<char>::from_text("ab\r\ncd\r\n").as_slice(), ['a', 'b', 'c', 'd']);
+        assert_eq!(Matrix::<char>::from_text(""), Matrix::default());
+    }
+
+    #[test]
+    fn parses_marked_text() {
+        let (matrix, marks) = Matrix::<char>::from_text_marked("S.#\n.SE\n", &['S', 'E', 'X']);
+        assert_eq!(matrix[(2, 1)], 'E');
+        assert_eq!(marks[&'S'], [Position::new(0, 0), Position::new(1, 1)]);
+        assert_eq!(marks[&'E'], [Position::new(2, 1)]);
+        assert!(!marks.contains_key(&'X'));
+    }
+
+    #[test]
+    fn reports_ragged_rows() {
+        let error = Matrix::from_text_with("abc\nab\n", |ch, _, _| Ok::<char, Infallible>(ch)).unwrap_err();
+        assert_eq!(error, MatrixParseError::Ragged { line: 2, width: 2, expected: 3 });
+        assert_eq!(error.to_string(), "line 2: row has 2 cells, expected 3");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid grid, line 3: row has 1 cells, expected 2")]
+    fn from_text_panics_on_ragged_rows() {
+        Matrix::<char>::from_text("ab\ncd\ne");
+    }
+
+    #[test]
+    fn parses_digits_and_numbers() {
+        assert_eq!(Matrix::<u8>::from_digits("012\n789\n").unwrap().as_slice(), [0, 1, 2, 7, 8, 9]);
+        let error = Matrix::<u8>::from_digits("012\n7x9\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 2: 'x' is not a digit");
+
+        let numbers = Matrix::<i32>::from_numbers("12  -3 7\n 1 2 3 \n").unwrap();
+        assert_eq!((numbers.width(), numbers.as_slice()), (3, [12, -3, 7, 1, 2, 3].as_slice()));
+        match Matrix::<i32>::from_numbers("1 2\n3 four\n").unwrap_err() {
+            MatrixParseError::Cell { line, column, .. } => assert_eq!((line, column), (2, 2)),
+            error => panic!("Expected a cell error, got {:?}", error),
         }
+    }
+
+    #[test]
+    fn parse_positions_are_grid_coordinates() {
+        let mut seen = Vec::new();
+        Matrix::from_text_with("ab\ncd", |ch, x, y| {
+            seen.push((ch, x, y));
+            Ok::<(), Infallible>(())
+        }).unwrap();
+        assert_eq!(seen, [('a', 0, 0), ('b', 1, 0), ('c', 0, 1), ('d', 1, 1)]);
+    }
 
-        Ok(())
+    #[test]
+    fn parses_several_grids() {
+        let grids = Matrix::<char>::grids_from_text("\n#.\n.#\n\n\n###\n\n..\n");
+        assert_eq!(grids.iter().map(|grid| (grid.width(), grid.height())).collect::<Vec<_>>(), [(2, 2), (3, 1), (2, 1)]);
+
+        let error = Matrix::grids_from_text_with("ab\n\ncde\nfg\n", |ch, _, _| Ok::<char, Infallible>(ch)).unwrap_err();
+        assert_eq!(error, MatrixParseError::Ragged { line: 4, width: 2, expected: 3 });
+
+        let mut rows = Vec::new();
+        Matrix::grids_from_text_with("a\n\nb\nc\n", |ch, _, y| {
+            rows.push((ch, y));
+            Ok::<(), Infallible>(())
+        }).unwrap();
+        assert_eq!(rows, [('a', 0), ('b', 0), ('c', 1)]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn diff_lists_changed_cells() {
+        let left = Matrix::<char>::from_text("ab\ncd\n");
+        let right = Matrix::<char>::from_text("ab\nxd\n");
+        assert_eq!(left.diff(&left.clone()), []);
+        assert_eq!(left.diff(&right), [CellChange { position: Position::new(0, 1), left: Some(&'c'), right: Some(&'x') }]);
+    }
+
+    #[test]
+    fn diff_lists_cells_outside_of_the_smaller_matrix() {
+        let left = Matrix::<char>::from_text("ab\n");
+        let right = Matrix::<char>::from_text("abc\ndef\n");
+        let changes = left.diff(&right);
+        assert_eq!(changes.iter().map(|change| change.position).collect::<Vec<_>>(), [
+            Position::new(2, 0),
+            Position::new(0, 1),
+            Position::new(1, 1),
+            Position::new(2, 1),
+        ]);
+        assert!(changes.iter().all(|change| change.left.is_none() && change.right.is_some()));
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_includes_the_dimensions() {
+        let row = Matrix::<u8>::new(4, 1);
+        assert_eq!(row.fingerprint(), row.clone().fingerprint());
+        assert_ne!(row.fingerprint(), Matrix::<u8>::new(2, 2).fingerprint());
+        assert_ne!(row.fingerprint(), row.map_xy(|_, x, _| x as u8).fingerprint());
+    }
+
+    #[test]
+    fn matrices_are_hash_map_keys() {
+        let mut seen = HashMap::new();
+        seen.insert(Matrix::<u8>::new(4, 1), 1);
+        seen.insert(Matrix::<u8>::new(2, 2), 2);
+        seen.insert(Matrix::<u8>::new(4, 1), 3);
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[&Matrix::new(4, 1)], 3);
+    }
+
+    #[test]
+    fn assert_matrix_eq_accepts_equal_matrices() {
+        assert_matrix_eq!(numbered(), numbered().rotate_180().rotate_180());
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrices differ in 1 cells")]
+    fn assert_matrix_eq_panics_with_the_number_of_changes() {
+        let mut changed = numbered();
+        changed[(2, 1)] = 99;
+        assert_matrix_eq!(numbered(), changed);
+    }
+}
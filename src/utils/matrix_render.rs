@@ -0,0 +1,255 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use ahash::AHashSet;
+use colored::{Color, ColoredString, Colorize};
+use crate::utils::matrix::Matrix;
+use crate::utils::position::Position;
+
+type CellStyle<'a, T> = Box<dyn Fn(&Position, &T, ColoredString) -> ColoredString + 'a>;
+
+/// Renders a matrix with styled cells, highlighted positions, coordinate rulers and cropping,
+/// created by `Matrix::render`. Cells are padded to the widest cell, and separated by a space
+/// when cells are wider than one character.
+pub struct MatrixRenderer<'a, T> {
+    matrix: &'a Matrix<T>,
+    style: Option<CellStyle<'a, T>>,
+    overlays: Vec<(AHashSet<Position>, char, Color)>,
+    rulers: bool,
+    viewport: Option<(Position, usize, usize)>,
+    plain: bool,
+}
+
+impl<T> Matrix<T>
+where
+    T: Display,
+{
+    pub fn render(&self) -> MatrixRenderer<'_, T> {
+        MatrixRenderer {
+            matrix: self,
+            style: None,
+            overlays: Vec::new(),
+            rulers: false,
+            viewport: None,
+            plain: false,
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Display + PartialEq,
+{
+    /// Renders this matrix, `other` and a map of the differing cells side by side, without colors.
+    pub fn render_diff(&self, other: &Matrix<T>) -> String {
+        let width = self.width().max(other.width());
+        let height = self.height().max(other.height());
+        let text = |matrix: &Matrix<T>, x: usize, y: usize| matrix.get(x, y).map(|cell| cell.to_string()).unwrap_or_default();
+        let cell_width = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| text(self, x, y).chars().count().max(text(other, x, y).chars().count()))
+            .max()
+            .unwrap_or(1);
+        let separator = if cell_width > 1 { " " } else { "" };
+        let grid_width = (width * (cell_width + separator.len())).saturating_sub(separator.len()).max("changes".len());
+
+        let mut lines = vec![format!("{:<w$}   {:<w$}   changes", "left", "right", w = grid_width)];
+        for y in 0..height {
+            let row = |cell: &dyn Fn(usize) -> String| {
+                (0..width).map(|x| format!("{:>w$}", cell(x), w = cell_width)).collect::<Vec<_>>().join(separator)
+            };
+            let left = row(&|x| text(self, x, y));
+            let right = row(&|x| text(other, x, y));
+            let changes = row(&|x| if self.get(x, y) == other.get(x, y) { ".".to_string() } else { "X".to_string() });
+            lines.push(format!("{:<w$}   {:<w$}   {}", left, right, changes, w = grid_width));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl<'a, T> MatrixRenderer<'a, T>
+where
+    T: Display,
+{
+    /// Styles every cell, the closure receives the padded cell text and returns it styled,
+    /// e.g. `|_, cell, text| if *cell == '#' { text.red() } else { text }`.
+    pub fn style(mut self, style: impl Fn(&Position, &T, ColoredString) -> ColoredString + 'a) -> Self {
+        self.style = Some(Box::new(style));
+        self
+    }
+
+    /// Draws `symbol` in the given color over the positions, e.g. to show a path. Later overlays
+    /// are drawn on top of earlier ones.
+    pub fn overlay(mut self, positions: impl IntoIterator<Item = Position>, symbol: char, color: Color) -> Self {
+        self.overlays.push((positions.into_iter().collect(), symbol, color));
+        self
+    }
+
+    /// Prints the column numbers above and the row numbers left of the grid.
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    /// Only renders a `width` x `height` window centered on `center`, shifted to stay inside the matrix.
+    pub fn viewport(mut self, center: Position, width: usize, height: usize) -> Self {
+        self.viewport = Some((center, width, height));
+        self
+    }
+
+    /// Leaves out all colors, for output that is compared as text.
+    pub fn plain(mut self) -> Self {
+        self.plain = true;
+        self
+    }
+
+    fn visible(&self) -> (Range<usize>, Range<usize>) {
+        let Some((center, width, height)) = self.viewport else {
+            return (0..self.matrix.width(), 0..self.matrix.height());
+        };
+
+        let window = |center: usize, size: usize, total: usize| {
+            let start = center.saturating_sub(size / 2).min(total.saturating_sub(size));
+            start..(start + size).min(total)
+        };
+        (window(center.x, width, self.matrix.width()), window(center.y, height, self.matrix.height()))
+    }
+
+    fn overlay_at(&self, position: &Position) -> Option<(char, Color)> {
+        self.overlays.iter()
+            .rev()
+            .find(|(positions, _, _)| positions.contains(position))
+            .map(|(_, symbol, color)| (*symbol, *color))
+    }
+}
+
+impl<T> Display for MatrixRenderer<'_, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (columns, rows) = self.visible();
+        let texts = rows.clone()
+            .map(|y| columns.clone().map(|x| self.matrix[(x, y)].to_string()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let cell_width = texts.iter().flatten().map(|text| text.chars().count()).max().unwrap_or(1);
+        let separator = if cell_width > 1 { " " } else { "" };
+        let label_width = rows.end.saturating_sub(1).to_string().len();
+
+        let mut lines = Vec::new();
+        if self.rulers {
+            let digits = columns.end.saturating_sub(1).to_string().len();
+            for digit in (0..digits).rev() {
+                let ruler = columns.clone()
+                    .map(|x| {
+                        let label = if digit == 0 || x >= 10usize.pow(digit as u32) {
+                            (x / 10usize.pow(digit as u32) % 10).to_string()
+                        } else {
+                            String::new()
+                        };
+                        format!("{:>width$}", label, width = cell_width)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(separator);
+                lines.push(format!("{:width$} {}", "", ruler, width = label_width));
+            }
+        }
+
+        for (y, row) in rows.zip(texts) {
+            let cells = columns.clone().zip(row)
+                .map(|(x, text)| {
+                    let position = Position::new(x, y);
+                    let overlay = self.overlay_at(&position);
+                    let text = match overlay {
+                        Some((symbol, _)) => format!("{:>width$}", symbol, width = cell_width),
+                        None => format!("{:>width$}", text, width = cell_width),
+                    };
+
+                    match (self.plain, overlay, &self.style) {
+                        (true, _, _) => text,
+                        (false, Some((_, color)), _) => text.color(color).bold().to_string(),
+                        (false, None, Some(style)) => style(&position, &self.matrix[position], text.normal()).to_string(),
+                        (false, None, None) => text,
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(separator);
+
+            if self.rulers {
+                lines.push(format!("{:>width$} {}", y, cells, width = label_width));
+            } else {
+                lines.push(cells);
+            }
+        }
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maze() -> Matrix<char> {
+        Matrix::from_text("#...\n.#..\n..#.\n")
+    }
+
+    #[test]
+    fn renders_plain_text() {
+        assert_eq!(maze().render().plain().to_string(), "#...\n.#..\n..#.");
+        assert_eq!(format!("{:?}", maze()), "#...\n.#..\n..#.");
+        assert_eq!(Matrix::<char>::default().render().plain().to_string(), "");
+    }
+
+    #[test]
+    fn pads_wide_cells() {
+        let numbers = Matrix::<i32>::from_numbers("1 -20\n300 4\n").unwrap();
+        assert_eq!(numbers.render().plain().to_string(), "  1 -20\n300   4");
+    }
+
+    #[test]
+    fn draws_overlays_on_top() {
+        let path = [Position::new(1, 0), Position::new(2, 0), Position::new(2, 1)];
+        let rendered = maze().render()
+            .overlay(path, 'o', Color::Green)
+            .overlay([Position::new(2, 1)], 'X', Color::Red)
+            .plain()
+            .to_string();
+        assert_eq!(rendered, "#oo.\n.#X.\n..#.");
+    }
+
+    #[test]
+    fn prints_rulers() {
+        let wide = Matrix::new_fill(12, 11, '.');
+        let rendered = wide.render().rulers().viewport(Position::new(11, 10), 4, 2).plain().to_string();
+        assert_eq!(rendered, "     11\n   8901\n 9 ....\n10 ....");
+
+        assert_eq!(maze().render().rulers().plain().to_string(), "  0123\n0 #...\n1 .#..\n2 ..#.");
+    }
+
+    #[test]
+    fn crops_to_the_viewport() {
+        assert_eq!(maze().render().viewport(Position::new(0, 0), 2, 2).plain().to_string(), "#.\n.#");
+        assert_eq!(maze().render().viewport(Position::new(2, 1), 2, 1).plain().to_string(), "#.");
+        assert_eq!(maze().render().viewport(Position::new(3, 2), 3, 2).plain().to_string(), "#..\n.#.");
+        assert_eq!(maze().render().viewport(Position::new(1, 1), 10, 10).plain().to_string(), "#...\n.#..\n..#.");
+    }
+
+    #[test]
+    fn renders_diffs_side_by_side() {
+        let mut other = maze();
+        other[(3, 2)] = '#';
+        assert_eq!(maze().render_diff(&other), concat!(
+            "left      right     changes\n",
+            "#...      #...      ....\n",
+            ".#..      .#..      ....\n",
+            "..#.      ..##      ...X"));
+
+        let numbers = Matrix::<u32>::from_numbers("1 2\n").unwrap();
+        let bigger = Matrix::<u32>::from_numbers("1 20\n3 4\n").unwrap();
+        assert_eq!(numbers.render_diff(&bigger), concat!(
+            "left      right     changes\n",
+            " 1  2      1 20      .  X\n",
+            "           3  4      X  X"));
+    }
+}
@@ -5,3 +5,23 @@ pub mod matrix;
 pub mod prefix_tree;
 pub mod position;
 pub mod facing;
+pub mod submit;
+pub mod calendar;
+pub mod badge;
+pub mod report;
+pub mod results_block;
+pub mod history;
+pub mod fingerprint;
+pub mod alloc_counter;
+pub mod pathfinding;
+pub mod sparse_grid;
+pub mod matrix_render;
+pub mod visualize;
+pub mod bit_matrix;
+pub mod cycle;
+pub mod vec2;
+pub mod point;
+pub mod grid_n;
+pub mod grid;
+#[cfg(test)]
+pub mod test_dir;
@@ -0,0 +1,446 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use ahash::{AHashMap, AHashSet};
+use crate::utils::matrix::{Connectivity, Matrix};
+use crate::utils::position::Position;
+
+/// Distances of a grid search from its sources, together with every predecessor of a cell that
+/// lies on one of its shortest paths.
+#[derive(Clone)]
+pub struct ShortestPaths {
+    pub distances: Matrix<Option<u64>>,
+    predecessors: Matrix<Vec<Position>>,
+}
+
+impl ShortestPaths {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            distances: Matrix::new_fill(width, height, None),
+            predecessors: Matrix::new_fill(width, height, Vec::new()),
+        }
+    }
+
+    pub fn distance(&self, position: Position) -> Option<u64> {
+        self.distances.get(position.x, position.y).copied().flatten()
+    }
+
+    pub fn predecessors(&self, position: Position) -> &[Position] {
+        &self.predecessors[position]
+    }
+
+    /// One shortest path from a source to `target`, both included.
+    pub fn path_to(&self, target: Position) -> Option<Vec<Position>> {
+        self.distance(target)?;
+
+        let mut path = vec![target];
+        while let Some(previous) = self.predecessors[*path.last().unwrap()].first() {
+            path.push(*previous);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Every cell lying on any shortest path from a source to `target`.
+    pub fn all_paths_to(&self, target: Position) -> AHashSet<Position> {
+        let mut cells = AHashSet::new();
+        if self.distance(target).is_none() {
+            return cells;
+        }
+
+        let mut stack = vec![target];
+        while let Some(position) = stack.pop() {
+            if cells.insert(position) {
+                stack.extend(self.predecessors[position].iter().copied());
+            }
+        }
+
+        cells
+    }
+
+    /// Records `distance` for `to` if it is at least as short as the known one. Returns whether
+    /// it is strictly shorter, i.e. whether `to` has to be expanded (again). Free steps don't add
+    /// alternative predecessors, as those could form cycles.
+    fn relax(&mut self, from: Position, to: Position, distance: u64) -> bool {
+        match self.distances[to] {
+            Some(known) if known < distance => false,
+            Some(known) if known == distance => {
+                if self.distances[from] != Some(distance) && !self.predecessors[to].contains(&from) {
+                    self.predecessors[to].push(from);
+                }
+                false
+            }
+            _ => {
+                self.distances[to] = Some(distance);
+                self.predecessors[to] = vec![from];
+                true
+            }
+        }
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Breadth first search from all `sources` at once. The predicate receives the cell being
+    /// expanded and its neighbour, and decides whether the step is allowed.
+    pub fn bfs<F>(&self, sources: &[Position], connectivity: Connectivity, mut passable: F) -> ShortestPaths
+    where
+        F: FnMut(&Position, &T, &Position, &T) -> bool,
+    {
+        let mut paths = ShortestPaths::new(self.width(), self.height());
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if paths.distances[*source].is_none() {
+                paths.distances[*source] = Some(0);
+                queue.push_back(*source);
+            }
+        }
+
+        while let Some(from) = queue.pop_front() {
+            let distance = paths.distances[from].unwrap();
+            for (to, cell) in self.neighbors(from.x, from.y, connectivity) {
+                if passable(&from, &self[from], &to, cell) && paths.relax(from, to, distance + 1) {
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Dijkstra from all `sources` at once over orthogonal steps. The cost closure receives the
+    /// cell being expanded, its neighbour and the neighbour's value, and returns `None` for
+    /// impassable steps.
+    pub fn dijkstra<F>(&self, sources: &[Position], mut cost: F) -> ShortestPaths
+    where
+        F: FnMut(&Position, &Position, &T) -> Option<u64>,
+    {
+        let mut paths = ShortestPaths::new(self.width(), self.height());
+        let mut heap = BinaryHeap::new();
+        for source in sources {
+            paths.distances[*source] = Some(0);
+            heap.push(Reverse((0, source.y, source.x)));
+        }
+
+        while let Some(Reverse((distance, y, x))) = heap.pop() {
+            let from = Position::new(x, y);
+            if paths.distances[from] != Some(distance) {
+                continue;
+            }
+
+            for (to, cell) in self.neighbors4(x, y) {
+                let Some(step) = cost(&from, &to, cell) else {
+                    continue;
+                };
+                if paths.relax(from, to, distance + step) {
+                    heap.push(Reverse((distance + step, to.y, to.x)));
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Finds a shortest path from `start` to `goal` over orthogonal steps, guided by the Manhattan
+    /// distance. Every step has to cost at least 1 for the heuristic to hold, use `dijkstra` otherwise.
+    pub fn astar<F>(&self, start: Position, goal: Position, mut cost: F) -> Option<(u64, Vec<Position>)>
+    where
+        F: FnMut(&Position, &Position, &T) -> Option<u64>,
+    {
+        let mut distances: Matrix<Option<u64>> = Matrix::new_fill(self.width(), self.height(), None);
+        let mut came_from: Matrix<Option<Position>> = Matrix::new_fill(self.width(), self.height(), None);
+        let mut heap = BinaryHeap::new();
+        distances[start] = Some(0);
+        heap.push(Reverse((start.manhattan(&goal) as u64, 0, start.y, start.x)));
+
+        while let Some(Reverse((_, distance, y, x))) = heap.pop() {
+            let from = Position::new(x, y);
+            if from == goal {
+                let mut path = vec![goal];
+                while let Some(previous) = came_from[*path.last().unwrap()] {
+                    path.push(previous);
+                }
+                path.reverse();
+                return Some((distance, path));
+            }
+            if distances[from] != Some(distance) {
+                continue;
+            }
+
+            for (to, cell) in self.neighbors4(x, y) {
+                let Some(step) = cost(&from, &to, cell) else {
+                    continue;
+                };
+                let next = distance + step;
+                if distances[to].is_none_or(|known| next < known) {
+                    distances[to] = Some(next);
+                    came_from[to] = Some(from);
+                    heap.push(Reverse((next + to.manhattan(&goal) as u64, next, to.y, to.x)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Distances of a search over arbitrary states, see `dijkstra`.
+#[derive(Debug, Clone)]
+pub struct StateSearch<S> {
+    states: Vec<S>,
+    index: AHashMap<S, usize>,
+    distances: Vec<u64>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<S> StateSearch<S>
+where
+    S: Clone + Eq + Hash,
+{
+    pub fn distance(&self, state: &S) -> Option<u64> {
+        self.index.get(state).map(|&index| self.distances[index])
+    }
+
+    /// The shortest distance of all reached states matching the predicate, e.g. the goal
+    /// position with any facing.
+    pub fn distance_where(&self, mut f: impl FnMut(&S) -> bool) -> Option<u64> {
+        self.reached().filter(|(state, _)| f(state)).map(|(_, distance)| distance).min()
+    }
+
+    /// All reached states with their distance, in the order they were first reached.
+    pub fn reached(&self) -> impl Iterator<Item = (&S, u64)> {
+        self.states.iter().zip(self.distances.iter().copied())
+    }
+
+    /// One shortest path from a start to `target`, both included.
+    pub fn path_to(&self, target: &S) -> Option<Vec<S>> {
+        let mut index = *self.index.get(target)?;
+        let mut path = vec![self.states[index].clone()];
+        while let Some(&previous) = self.predecessors[index].first() {
+            path.push(self.states[previous].clone());
+            index = previous;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Every state lying on any shortest path to the closest of the reached `targets`.
+    pub fn all_paths_to<'a>(&self, targets: impl IntoIterator<Item = &'a S>) -> AHashSet<S>
+    where
+        S: 'a,
+    {
+        let targets = targets.into_iter()
+            .filter_map(|target| self.index.get(target).copied())
+            .collect::<Vec<_>>();
+        let Some(best) = targets.iter().map(|&index| self.distances[index]).min() else {
+            return AHashSet::new();
+        };
+
+        let mut visited = vec![false; self.states.len()];
+        let mut stack = targets.into_iter().filter(|&index| self.distances[index] == best).collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            if !visited[index] {
+                visited[index] = true;
+                stack.extend(self.predecessors[index].iter().copied());
+            }
+        }
+
+        visited.iter()
+            .zip(&self.states)
+            .filter(|(visited, _)| **visited)
+            .map(|(_, state)| state.clone())
+            .collect()
+    }
+
+    fn intern(&mut self, state: S) -> usize {
+        if let Some(&index) = self.index.get(&state) {
+            return index;
+        }
+
+        let index = self.states.len();
+        self.index.insert(state.clone(), index);
+        self.states.push(state);
+        self.distances.push(u64::MAX);
+        self.predecessors.push(Vec::new());
+        index
+    }
+
+    /// Collapses the states onto a grid, keeping the shortest distance of all states that share a position.
+    pub fn project(&self, width: usize, height: usize, position: impl Fn(&S) -> Position) -> Matrix<Option<u64>> {
+        let mut distances: Matrix<Option<u64>> = Matrix::new_fill(width, height, None);
+        for (state, distance) in self.reached() {
+            let position = position(state);
+            if distances[position].is_none_or(|known| distance < known) {
+                distances[position] = Some(distance);
+            }
+        }
+
+        distances
+    }
+}
+
+/// Dijkstra over arbitrary search states, for searches where the position alone isn't enough,
+/// like a position combined with a `Facing` to charge for turns. `successors` returns the states
+/// reachable from a state together with the cost of getting there.
+pub fn dijkstra<S, I>(starts: impl IntoIterator<Item = S>, mut successors: impl FnMut(&S) -> I) -> StateSearch<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut search = StateSearch {
+        states: Vec::new(),
+        index: AHashMap::new(),
+        distances: Vec::new(),
+        predecessors: Vec::new(),
+    };
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        let index = search.intern(start);
+        search.distances[index] = 0;
+        heap.push(Reverse((0, index)));
+    }
+
+    while let Some(Reverse((distance, from))) = heap.pop() {
+        if search.distances[from] != distance {
+            continue;
+        }
+
+        let state = search.states[from].clone();
+        for (next, cost) in successors(&state) {
+            let to = search.intern(next);
+            let next_distance = distance + cost;
+            let known = search.distances[to];
+            if next_distance < known {
+                search.distances[to] = next_distance;
+                search.predecessors[to] = vec![from];
+                heap.push(Reverse((next_distance, to)));
+            } else if next_distance == known && cost > 0 && !search.predecessors[to].contains(&from) {
+                search.predecessors[to].push(from);
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::facing::Facing;
+    use super::*;
+
+    const MAZE: &str = "S..#\n.#..\n...E\n";
+
+    fn maze() -> Matrix<char> {
+        Matrix::from_text(MAZE)
+    }
+
+    fn open(_: &Position, _: &char, _: &Position, to: &char) -> bool {
+        *to != '#'
+    }
+
+    #[test]
+    fn bfs_finds_every_shortest_path() {
+        let paths = maze().bfs(&[Position::new(0, 0)], Connectivity::Four, open);
+        assert_eq!(paths.distance(Position::new(3, 2)), Some(5));
+        assert_eq!(paths.distance(Position::new(3, 0)), None);
+        assert_eq!(paths.distance(Position::new(9, 9)), None);
+        assert_eq!(paths.predecessors(Position::new(2, 1)), [Position::new(2, 0)]);
+        assert_eq!(paths.predecessors(Position::new(3, 2)).len(), 2);
+
+        let path = paths.path_to(Position::new(3, 2)).unwrap();
+        assert_eq!((path.len(), path[0], path[5]), (6, Position::new(0, 0), Position::new(3, 2)));
+        assert!(path.windows(2).all(|step| step[0].manhattan(&step[1]) == 1));
+        assert_eq!(paths.path_to(Position::new(1, 1)), None);
+
+        assert_eq!(paths.all_paths_to(Position::new(3, 2)).len(), 10);
+        assert!(paths.all_paths_to(Position::new(3, 0)).is_empty());
+    }
+
+    #[test]
+    fn bfs_from_several_sources() {
+        let sources = [Position::new(0, 0), Position::new(3, 2), Position::new(0, 0)];
+        let paths = maze().bfs(&sources, Connectivity::Four, open);
+        assert_eq!(paths.distance(Position::new(0, 2)), Some(2));
+        assert_eq!(paths.distance(Position::new(2, 0)), Some(2));
+        assert_eq!(paths.path_to(Position::new(3, 2)), Some(vec![Position::new(3, 2)]));
+
+        let diagonal = maze().bfs(&[Position::new(0, 0)], Connectivity::Eight, open);
+        assert_eq!(diagonal.distance(Position::new(3, 2)), Some(3));
+    }
+
+    #[test]
+    fn dijkstra_and_astar_agree() {
+        let costs = Matrix::<u8>::from_digits("1163751\n1381373\n2136511\n3694931\n7463417\n").unwrap();
+        let cost = |_: &Position, _: &Position, cell: &u8| Some(*cell as u64);
+        let (start, goal) = (Position::new(0, 0), Position::new(6, 4));
+
+        let paths = costs.dijkstra(&[start], cost);
+        let (distance, path) = costs.astar(start, goal, cost).unwrap();
+        assert_eq!(paths.distance(goal), Some(distance));
+        assert_eq!(path.iter().skip(1).map(|position| costs[*position] as u64).sum::<u64>(), distance);
+        assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+        assert_eq!(paths.path_to(goal).unwrap().len(), paths.path_to(goal).unwrap().iter().collect::<AHashSet<_>>().len());
+    }
+
+    #[test]
+    fn impassable_steps() {
+        let walls = |_: &Position, _: &Position, cell: &char| (*cell != '#').then_some(1);
+        let closed = Matrix::<char>::from_text("S#.\n##.\n..E\n");
+        assert_eq!(closed.astar(Position::new(0, 0), Position::new(2, 2), walls), None);
+        assert_eq!(closed.dijkstra(&[Position::new(0, 0)], walls).distance(Position::new(2, 2)), None);
+        assert_eq!(maze().astar(Position::new(0, 0), Position::new(0, 0), walls), Some((0, vec![Position::new(0, 0)])));
+    }
+
+    #[test]
+    fn free_steps_keep_paths_acyclic() {
+        let zero = Matrix::new_fill(3, 1, 0u8);
+        let paths = zero.dijkstra(&[Position::new(0, 0)], |_, _, _| Some(0));
+        assert_eq!(paths.distance(Position::new(2, 0)), Some(0));
+        assert_eq!(paths.path_to(Position::new(2, 0)).unwrap().len(), 3);
+        assert_eq!(paths.all_paths_to(Position::new(2, 0)).len(), 3);
+    }
+
+    /// Moving costs 1, turning a quarter costs 1000, like the reindeer maze of 2024.
+    fn reindeer(maze: &Matrix<char>) -> StateSearch<(Position, Facing)> {
+        dijkstra([(Position::new(0, 2), Facing::East)], |&(position, facing)| {
+            let mut next = vec![((position, facing.turn_left()), 1000), ((position, facing.turn_right()), 1000)];
+            if let Some(ahead) = maze.step(&position, facing).filter(|ahead| maze[*ahead] != '#') {
+                next.push(((ahead, facing), 1));
+            }
+            next
+        })
+    }
+
+    #[test]
+    fn state_search_charges_for_turns() {
+        let maze = Matrix::<char>::from_text("..E\n.#.\nS..\n");
+        let search = reindeer(&maze);
+        let goal = Position::new(2, 0);
+
+        assert_eq!(search.distance_where(|(position, _)| *position == goal), Some(1004));
+        assert_eq!(search.distance(&(goal, Facing::North)), Some(1004));
+        assert_eq!(search.distance(&(goal, Facing::South)), Some(3004));
+        assert_eq!(search.distance(&(Position::new(1, 1), Facing::North)), None);
+
+        let path = search.path_to(&(goal, Facing::North)).unwrap();
+        assert_eq!(path.first(), Some(&(Position::new(0, 2), Facing::East)));
+        assert_eq!(path.iter().filter(|(position, _)| *position == Position::new(2, 2)).count(), 2);
+
+        let targets = Facing::ALL.map(|facing| (goal, facing));
+        let cells = search.all_paths_to(&targets).into_iter().map(|(position, _)| position).collect::<AHashSet<_>>();
+        assert_eq!(cells.len(), 5);
+        assert!(search.all_paths_to(&[(Position::new(1, 1), Facing::North)]).is_empty());
+    }
+
+    #[test]
+    fn state_search_projects_onto_the_grid() {
+        let maze = Matrix::<char>::from_text("..E\n.#.\nS..\n");
+        let distances = reindeer(&maze).project(3, 3, |(position, _)| *position);
+        assert_eq!(distances.row(2), [Some(0), Some(1), Some(2)]);
+        assert_eq!(distances[(1, 1)], None);
+        assert_eq!(distances[(0, 1)], Some(1001));
+        assert_eq!(reindeer(&maze).reached().next(), Some((&(Position::new(0, 2), Facing::East), 0)));
+    }
+}
@@ -0,0 +1,333 @@
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use crate::utils::matrix::Connectivity;
+use crate::utils::position::Position;
+use crate::utils::sparse_grid::Bounds;
+use crate::utils::vec2::Vec2;
+
+/// A point with `N` signed coordinates, for puzzles that play out in 3D, 4D or more. Axis 0
+/// is x, axis 1 is y and so on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+pub type Point3 = Point<3>;
+pub type Point4 = Point<4>;
+
+impl<const N: usize> Point<N> {
+    pub const ZERO: Point<N> = Point([0; N]);
+
+    pub fn new(coordinates: [i64; N]) -> Self {
+        Self(coordinates)
+    }
+
+    /// Embeds a 2D position, with every further coordinate set to 0.
+    pub fn from_position(position: Position) -> Self {
+        if N < 2 {
+            panic!("Can't embed a 2D position into {N} dimensions");
+        }
+
+        let mut point = Self::ZERO;
+        point.0[0] = position.x as i64;
+        point.0[1] = position.y as i64;
+        point
+    }
+
+    pub fn manhattan(&self, other: &Point<N>) -> u64 {
+        self.0.iter().zip(&other.0).map(|(a, b)| a.abs_diff(*b)).sum()
+    }
+
+    /// The number of king moves between the points.
+    pub fn chebyshev(&self, other: &Point<N>) -> u64 {
+        self.0.iter().zip(&other.0).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0)
+    }
+
+    /// The steps to the neighbours of a point. `Four` gives the 2N neighbours sharing a face,
+    /// `Eight` all 3^N - 1 neighbours including the diagonal ones, like in 2D.
+    pub fn offsets(connectivity: Connectivity) -> Vec<Point<N>> {
+        match connectivity {
+            Connectivity::Four => (0..N)
+                .flat_map(|axis| [-1, 1].map(|step| {
+                    let mut offset = Self::ZERO;
+                    offset.0[axis] = step;
+                    offset
+                }))
+                .collect(),
+            Connectivity::Eight => (0..3usize.pow(N as u32))
+                .map(|mut digits| {
+                    let mut offset = Self::ZERO;
+                    for coordinate in offset.0.iter_mut() {
+                        *coordinate = (digits % 3) as i64 - 1;
+                        digits /= 3;
+                    }
+                    offset
+                })
+                .filter(|offset| *offset != Self::ZERO)
+                .collect(),
+        }
+    }
+
+    pub fn neighbors(&self, connectivity: Connectivity) -> impl Iterator<Item = Point<N>> + use<N> {
+        let point = *self;
+        Self::offsets(connectivity).into_iter().map(move |offset| point + offset)
+    }
+
+    /// The 2N neighbours sharing a face with this point.
+    pub fn face_neighbors(&self) -> impl Iterator<Item = Point<N>> + use<N> {
+        self.neighbors(Connectivity::Four)
+    }
+
+    /// All 3^N - 1 surrounding points.
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Point<N>> + use<N> {
+        self.neighbors(Connectivity::Eight)
+    }
+
+    fn zip_with(self, other: Point<N>, f: impl Fn(i64, i64) -> i64) -> Point<N> {
+        Point(std::array::from_fn(|axis| f(self.0[axis], other.0[axis])))
+    }
+}
+
+impl<const N: usize> Default for Point<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const N: usize> From<[i64; N]> for Point<N> {
+    fn from(coordinates: [i64; N]) -> Self {
+        Point(coordinates)
+    }
+}
+
+impl From<Vec2> for Point<2> {
+    fn from(vec: Vec2) -> Self {
+        Point([vec.x, vec.y])
+    }
+}
+
+impl From<Point<2>> for Vec2 {
+    fn from(point: Point<2>) -> Self {
+        Vec2::new(point.0[0], point.0[1])
+    }
+}
+
+impl From<Position> for Point<2> {
+    fn from(position: Position) -> Self {
+        Point::from_position(position)
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Point<N>;
+
+    fn add(self, other: Point<N>) -> Self::Output {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Point<N>) {
+        *self = *self + other;
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Point<N>;
+
+    fn sub(self, other: Point<N>) -> Self::Output {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Point<N>) {
+        *self = *self - other;
+    }
+}
+
+impl<const N: usize> Mul<i64> for Point<N> {
+    type Output = Point<N>;
+
+    fn mul(self, factor: i64) -> Self::Output {
+        Point(self.0.map(|coordinate| coordinate * factor))
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Point<N>;
+
+    fn neg(self) -> Self::Output {
+        Point(self.0.map(|coordinate| -coordinate))
+    }
+}
+
+/// The smallest box containing a set of points, both corners included. The N-dimensional
+/// counterpart of `Bounds`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BoundingBox<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+impl<const N: usize> BoundingBox<N> {
+    pub fn new(min: Point<N>, max: Point<N>) -> Self {
+        Self { min, max }
+    }
+
+    /// The box around all points, `None` when there are none.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<N>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(Self::new(first, first), |bounds, point| bounds.including(point)))
+    }
+
+    /// The box grown just enough to contain `point`.
+    pub fn including(&self, point: &Point<N>) -> Self {
+        Self::new(self.min.zip_with(*point, i64::min), self.max.zip_with(*point, i64::max))
+    }
+
+    /// The box grown by `amount` on every side, e.g. 1 to make room for the next step of a
+    /// cellular automaton.
+    pub fn grown(&self, amount: i64) -> Self {
+        Self::new(Point(self.min.0.map(|min| min - amount)), Point(self.max.0.map(|max| max + amount)))
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        (0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// The number of points along every axis.
+    pub fn size(&self) -> [usize; N] {
+        std::array::from_fn(|axis| (self.max[axis] - self.min[axis] + 1).max(0) as usize)
+    }
+
+    /// The number of points in the box.
+    pub fn volume(&self) -> usize {
+        self.size().iter().product()
+    }
+
+    /// The position of `point` when the box is stored flat with axis 0 changing fastest.
+    pub fn index_of(&self, point: &Point<N>) -> Option<usize> {
+        if !self.contains(point) {
+            return None;
+        }
+
+        let size = self.size();
+        Some((0..N).rev().fold(0, |index, axis| index * size[axis] + (point[axis] - self.min[axis]) as usize))
+    }
+
+    /// The point stored at `index`, the reverse of `index_of`.
+    pub fn point_at(&self, mut index: usize) -> Point<N> {
+        let size = self.size();
+        let mut point = self.min;
+        for axis in 0..N {
+            point[axis] += (index % size[axis]) as i64;
+            index /= size[axis];
+        }
+
+        point
+    }
+
+    /// Every point in the box, with axis 0 changing fastest.
+    pub fn points(&self) -> impl Iterator<Item = Point<N>> + use<N> {
+        let bounds = *self;
+        (0..self.volume()).map(move |index| bounds.point_at(index))
+    }
+}
+
+impl From<Bounds> for BoundingBox<2> {
+    fn from(bounds: Bounds) -> Self {
+        BoundingBox::new(Point([bounds.min_x, bounds.min_y]), Point([bounds.max_x, bounds.max_y]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_distances() {
+        let a = Point3::new([1, -2, 3]);
+        let b = Point3::new([-1, 2, 0]);
+        assert_eq!(a + b, Point([0, 0, 3]));
+        assert_eq!(a - b, Point([2, -4, 3]));
+        assert_eq!(a * 2, Point([2, -4, 6]));
+        assert_eq!(-a, Point([-1, 2, -3]));
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(Point::<0>::ZERO.chebyshev(&Point::ZERO), 0);
+
+        let mut c = a;
+        c += b;
+        c -= Point([0, 0, 3]);
+        c[2] += 1;
+        assert_eq!(c, Point([0, 0, 1]));
+    }
+
+    #[test]
+    fn converts_from_two_dimensions() {
+        assert_eq!(Point3::from_position(Position::new(4, 5)), Point([4, 5, 0]));
+        assert_eq!(Point::from(Position::new(4, 5)), Point([4, 5]));
+        assert_eq!(Point::from(Vec2::new(-4, 5)), Point([-4, 5]));
+        assert_eq!(Vec2::from(Point([-4, 5])), Vec2::new(-4, 5));
+        assert_eq!(Point::from([1, 2, 3, 4]), Point4::new([1, 2, 3, 4]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't embed a 2D position into 1 dimensions")]
+    fn from_position_needs_two_dimensions() {
+        Point::<1>::from_position(Position::new(0, 0));
+    }
+
+    #[test]
+    fn offsets_cover_faces_or_all_surrounding_points() {
+        assert_eq!(Point::<2>::offsets(Connectivity::Four), [Point([-1, 0]), Point([1, 0]), Point([0, -1]), Point([0, 1])]);
+        assert_eq!(Point::<2>::offsets(Connectivity::Eight).len(), 8);
+        assert_eq!(Point3::offsets(Connectivity::Four).len(), 6);
+        assert_eq!(Point3::offsets(Connectivity::Eight).len(), 26);
+        assert_eq!(Point4::offsets(Connectivity::Four).len(), 8);
+        assert_eq!(Point4::offsets(Connectivity::Eight).len(), 80);
+
+        let point = Point3::new([5, 5, 5]);
+        assert!(point.face_neighbors().all(|neighbor| neighbor.manhattan(&point) == 1));
+        assert!(point.all_neighbors().all(|neighbor| neighbor.chebyshev(&point) == 1));
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let points = [Point([2, -1, 0]), Point([-1, 3, 0]), Point([0, 0, 1])];
+        let bounds = BoundingBox::from_points(&points).unwrap();
+        assert_eq!(bounds, BoundingBox::new(Point([-1, -1, 0]), Point([2, 3, 1])));
+        assert_eq!(bounds.size(), [4, 5, 2]);
+        assert_eq!(bounds.volume(), 40);
+        assert!(points.iter().all(|point| bounds.contains(point)));
+        assert!(!bounds.contains(&Point([0, 0, 2])));
+        assert_eq!(bounds.grown(1).size(), [6, 7, 4]);
+        assert_eq!(bounds.including(&Point([5, 0, 0])).max, Point([5, 3, 1]));
+        assert_eq!(BoundingBox::<3>::from_points(&[]), None);
+        assert_eq!(BoundingBox::from(Bounds { min_x: -1, min_y: 2, max_x: 3, max_y: 4 }), BoundingBox::new(Point([-1, 2]), Point([3, 4])));
+    }
+
+    #[test]
+    fn points_are_stored_with_axis_0_changing_fastest() {
+        let bounds = BoundingBox::new(Point([-1, 0]), Point([1, 1]));
+        assert_eq!(bounds.points().collect::<Vec<_>>(), [
+            Point([-1, 0]), Point([0, 0]), Point([1, 0]),
+            Point([-1, 1]), Point([0, 1]), Point([1, 1]),
+        ]);
+        assert!(bounds.points().enumerate().all(|(index, point)| bounds.index_of(&point) == Some(index)));
+        assert_eq!(bounds.index_of(&Point([2, 0])), None);
+    }
+}
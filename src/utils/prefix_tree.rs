@@ -1,6 +1,6 @@
 use ahash::{AHashMap, AHashSet};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct PrefixTree {
     children: AHashMap<char, Box<PrefixTree>>,
     is_leaf: bool,
@@ -8,7 +8,7 @@ pub struct PrefixTree {
 
 impl PrefixTree {
     pub fn new() -> Self {
-        Self { children: AHashMap::new(), is_leaf: false }
+        Self::default()
     }
 
     pub fn from_vec(items: Vec<&str>) -> Self {
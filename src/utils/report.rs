@@ -0,0 +1,603 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use colored::Colorize;
+use crate::utils::aoc::format_elapsed;
+use crate::utils::badge::{BadgeStatus, ReportAsset, ReportStyle};
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::results_block::{parse_blocks, replace_bodies, write_atomic, ReportError, ResultsBlock, BLOCK_START};
+use crate::utils::test_set::TestRunResult;
+
+const DEFAULT_README: &str = "README.md";
+const DEFAULT_SOURCE: &str = "src/days/day{day}.rs";
+const DEFAULT_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ReportColumn {
+    Day,
+    Title,
+    Status,
+    Bar,
+    Part1,
+    Part2,
+    Total,
+    Delta,
+}
+
+impl ReportColumn {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "day" => Some(ReportColumn::Day),
+            "title" => Some(ReportColumn::Title),
+            "status" => Some(ReportColumn::Status),
+            "bar" => Some(ReportColumn::Bar),
+            "part1" => Some(ReportColumn::Part1),
+            "part2" => Some(ReportColumn::Part2),
+            "total" => Some(ReportColumn::Total),
+            "delta" => Some(ReportColumn::Delta),
+            _ => None,
+        }
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            ReportColumn::Day => "Day",
+            ReportColumn::Title => "Title",
+            ReportColumn::Status => "Status",
+            ReportColumn::Bar => "Execution Time Comparison",
+            ReportColumn::Part1 => "Part 1",
+            ReportColumn::Part2 => "Part 2",
+            ReportColumn::Total => "Execution Time",
+            ReportColumn::Delta => "Change",
+        }
+    }
+
+    /// Timing columns change on every run, so they are left out of the layout fingerprint.
+    fn is_timing(&self) -> bool {
+        matches!(self, ReportColumn::Bar | ReportColumn::Part1 | ReportColumn::Part2 | ReportColumn::Total | ReportColumn::Delta)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    List,
+}
+
+/// Options of a single results block, read from its start marker, e.g.
+/// `<!-- {RESULTS_START columns=day,title,status,total,delta format=table year=2025} -->`.
+///
+/// Supported options are `columns`, `format` (`table` or `list`), `style` (`remote`, `svg` or
+/// `unicode`), `year`, `threshold` (the timing change in percent that triggers a rewrite) and
+/// `source` (the link target of the day column, with `{year}` and `{day}` placeholders).
+#[derive(Debug, Clone)]
+pub struct BlockOptions {
+    pub columns: Vec<ReportColumn>,
+    pub format: ReportFormat,
+    pub style: Option<ReportStyle>,
+    pub year: Option<i32>,
+    pub threshold: f64,
+    pub source: String,
+}
+
+impl Default for BlockOptions {
+    fn default() -> Self {
+        Self {
+            columns: vec![ReportColumn::Day, ReportColumn::Status, ReportColumn::Bar, ReportColumn::Total],
+            format: ReportFormat::Table,
+            style: None,
+            year: None,
+            threshold: DEFAULT_THRESHOLD,
+            source: DEFAULT_SOURCE.to_string(),
+        }
+    }
+}
+
+impl BlockOptions {
+    pub fn parse(marker_line: &str) -> Result<Self, String> {
+        let mut options = Self::default();
+        let start = marker_line.find(BLOCK_START).ok_or(format!("Missing {}", BLOCK_START))? + BLOCK_START.len();
+        let end = start + marker_line[start..].rfind('}').ok_or("Unterminated results marker".to_string())?;
+
+        for option in marker_line[start..end].split_whitespace() {
+            let (key, value) = option.split_once('=')
+                .ok_or(format!("Invalid report option '{}', expected key=value", option))?;
+            match key {
+                "columns" => {
+                    options.columns = value.split(',')
+                        .map(|name| ReportColumn::parse(name).ok_or(format!("Unknown report column '{}'", name)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "format" => {
+                    options.format = match value {
+                        "table" => ReportFormat::Table,
+                        "list" => ReportFormat::List,
+                        _ => return Err(format!("Unknown report format '{}'", value)),
+                    };
+                }
+                "style" => {
+                    options.style = Some(match value {
+                        "remote" => ReportStyle::Remote,
+                        "svg" => ReportStyle::LocalSvg,
+                        "unicode" => ReportStyle::Unicode,
+                        _ => return Err(format!("Unknown report style '{}'", value)),
+                    });
+                }
+                "year" => options.year = Some(value.parse().map_err(|_| format!("Invalid report year '{}'", value))?),
+                "threshold" => options.threshold = value.parse().map_err(|_| format!("Invalid report threshold '{}'", value))?,
+                "source" => options.source = value.to_string(),
+                _ => return Err(format!("Unknown report option '{}'", key)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub struct DayReport<'a> {
+    pub day: u8,
+    pub title: &'a str,
+    pub result: &'a Option<TestRunResult>,
+}
+
+/// The results of one `solve_all` run, as rendered into the report blocks.
+pub struct ReportData<'a> {
+    pub year: i32,
+    pub days: Vec<DayReport<'a>>,
+}
+
+/// Where and how the results blocks are written.
+///
+/// Without any explicitly added files, the `README.md` found in the current directory or
+/// any of its parents is used.
+pub struct ReportConfig {
+    pub files: Vec<PathBuf>,
+    pub style: ReportStyle,
+    pub dry_run: bool,
+    /// Overwrites timings that were measured with a different machine fingerprint.
+    pub force: bool,
+    pub default_year: i32,
+    pub fingerprint: Fingerprint,
+}
+
+impl ReportConfig {
+    pub fn new(default_year: i32) -> Self {
+        Self {
+            files: Vec::new(),
+            style: ReportStyle::default(),
+            dry_run: false,
+            force: false,
+            default_year,
+            fingerprint: Fingerprint::current(),
+        }
+    }
+
+    fn resolve_files(&self) -> Vec<PathBuf> {
+        if !self.files.is_empty() {
+            return self.files.clone();
+        }
+
+        let mut root = std::env::current_dir().unwrap();
+        while !root.join(DEFAULT_README).exists() {
+            match root.parent() {
+                Some(parent) => root = parent.to_path_buf(),
+                None => return Vec::new(),
+            }
+        }
+
+        vec![root.join(DEFAULT_README)]
+    }
+
+    pub fn write(&self, data: &ReportData) {
+        if cfg!(debug_assertions) && !self.dry_run {
+            return;
+        }
+
+        for file in self.resolve_files() {
+            if let Err(error) = self.write_file(&file, data) {
+                println!("{} {}", "Failed to update the results report:".red().bold(), error);
+            }
+        }
+    }
+
+    fn write_file(&self, path: &Path, data: &ReportData) -> Result<(), ReportError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|error| ReportError::Io { path: path.to_path_buf(), error })?;
+        let blocks = parse_blocks(&content)?;
+        let mut assets = Vec::new();
+        let mut should_rewrite = false;
+
+        let mut bodies = Vec::new();
+        for block in &blocks {
+            if block.options.year.unwrap_or(self.default_year) != data.year {
+                bodies.push(None);
+                continue;
+            }
+
+            // Timings without a MACHINE comment predate the fingerprint, so their machine is unknown.
+            let measured_on = match &block.machine {
+                Some((id, summary)) if *id != self.fingerprint.id() => Some(summary.as_str()),
+                None if block.baseline.values().any(|micros| *micros != u128::MAX) => Some("an unrecorded machine"),
+                _ => None,
+            };
+            if let Some(measured_on) = measured_on && !self.force {
+                println!("{} the block on line {} of {} was measured on {}, use --force to replace it with timings from {}",
+                         "Skipping results block:".yellow().bold(), block.start_line, path.display(), measured_on, self.fingerprint.summary());
+                bodies.push(None);
+                continue;
+            }
+
+            let (lines, changed) = self.render_block(block, data, &mut assets);
+            should_rewrite |= changed;
+            bodies.push(Some(lines.iter().map(|line| format!("{}{}", line, block.line_ending)).collect::<String>()));
+        }
+
+        if !should_rewrite {
+            return Ok(());
+        }
+
+        let new_content = replace_bodies(&content, &blocks, &bodies);
+        if self.dry_run {
+            print_diff(path, &content, &new_content);
+            return Ok(());
+        }
+
+        let root = path.parent().unwrap();
+        for asset in &assets {
+            asset.write(root)?;
+        }
+        write_atomic(path, &new_content)
+    }
+
+    /// Renders the block and reports whether the file needs to be rewritten, which is the case
+    /// when the layout changed or any timing differs from the stored one by the block's threshold.
+    fn render_block(&self, block: &ResultsBlock, data: &ReportData, assets: &mut Vec<ReportAsset>) -> (Vec<String>, bool) {
+        let options = &block.options;
+        let style = options.style.unwrap_or(self.style);
+        let baseline = &block.baseline;
+
+        let mut changed = false;
+        for day in &data.days {
+            let (Some(result), Some(stored_micros)) = (day.result, baseline.get(&day.day).filter(|micros| **micros != u128::MAX)) else {
+                continue;
+            };
+            let mut stored_micros = *stored_micros as f64;
+            let mut result_micros = result.elapsed.as_micros() as f64;
+            if stored_micros > result_micros {
+                (stored_micros, result_micros) = (result_micros, stored_micros);
+            }
+            if 1.0 - stored_micros / result_micros >= options.threshold / 100.0 {
+                changed = true;
+            }
+        }
+
+        let layout = format!("{:016x}", fnv1a(&format!("{:?}\n{}", style,
+            render_rows(options, style, data, baseline, false, &mut Vec::new()).join("\n"))));
+        if block.layout.as_deref() != Some(layout.as_str()) || block.machine.is_none() {
+            changed = true;
+        }
+
+        let mut lines = render_rows(options, style, data, baseline, true, assets);
+        for day in &data.days {
+            let micros = day.result.as_ref().map(|result| result.elapsed.as_micros()).unwrap_or(u128::MAX);
+            lines.push(format!("<!-- {{DAY {}={}}} -->", day.day, micros));
+        }
+        lines.push(format!("<!-- {{LAYOUT {}}} -->", layout));
+        lines.push(format!("<!-- {{MACHINE {} {}}} -->", self.fingerprint.id(), self.fingerprint.summary()));
+
+        (lines, changed)
+    }
+}
+
+fn render_rows(options: &BlockOptions, style: ReportStyle, data: &ReportData, baseline: &HashMap<u8, u128>, with_timings: bool, assets: &mut Vec<ReportAsset>) -> Vec<String> {
+    let longest_duration = longest_duration(data.days.iter().map(|day| day.result));
+    let mut lines = Vec::new();
+
+    if options.format == ReportFormat::Table {
+        let headers = options.columns.iter().map(|column| format!(" {} ", column.header())).collect::<Vec<_>>();
+        lines.push(format!("|{}|", headers.join("|")));
+        lines.push(format!("|{}|", headers.iter().map(|header| "-".repeat(header.len())).collect::<Vec<_>>().join("|")));
+    }
+
+    for day in &data.days {
+        let cells = options.columns.iter()
+            .map(|column| {
+                if column.is_timing() && !with_timings {
+                    return String::new();
+                }
+                render_cell(*column, options, style, data.year, day, &longest_duration, baseline, assets)
+            })
+            .collect::<Vec<_>>();
+
+        match options.format {
+            ReportFormat::Table => lines.push(format!("|{}|", cells.join("|"))),
+            ReportFormat::List => lines.push(format!("- {}", cells.into_iter().filter(|cell| !cell.is_empty()).collect::<Vec<_>>().join(" · "))),
+        }
+    }
+
+    lines
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_cell(column: ReportColumn, options: &BlockOptions, style: ReportStyle, year: i32, day: &DayReport, longest_duration: &Duration, baseline: &HashMap<u8, u128>, assets: &mut Vec<ReportAsset>) -> String {
+    let format_part = |elapsed: Option<Duration>| elapsed.map(|elapsed| format_elapsed(elapsed, false)).unwrap_or("-".to_string());
+
+    match column {
+        ReportColumn::Day => {
+            let source = options.source.replace("{year}", &year.to_string()).replace("{day}", &day.day.to_string());
+            match options.format {
+                ReportFormat::Table => format!("[{}]({})", day.day, source),
+                ReportFormat::List => format!("[Day {}]({})", day.day, source),
+            }
+        }
+        ReportColumn::Title => day.title.to_string(),
+        ReportColumn::Status => style.status_badge(BadgeStatus::from_result(day.result), assets),
+        ReportColumn::Bar => match day.result {
+            Some(result) => {
+                let percentage = 100 * result.elapsed.as_micros() / longest_duration.as_micros().max(1);
+                style.progress_bar(year, day.day, percentage, assets)
+            }
+            None => String::new(),
+        },
+        ReportColumn::Part1 => day.result.as_ref().map(|result| format_part(result.part1_elapsed)).unwrap_or_default(),
+        ReportColumn::Part2 => day.result.as_ref().map(|result| format_part(result.part2_elapsed)).unwrap_or_default(),
+        ReportColumn::Total => day.result.as_ref().map(|result| format_elapsed(result.elapsed, false)).unwrap_or_default(),
+        ReportColumn::Delta => {
+            let stored_micros = baseline.get(&day.day).filter(|micros| **micros != u128::MAX);
+            match (day.result, stored_micros) {
+                (Some(result), Some(stored_micros)) => {
+                    let change = 100.0 * (result.elapsed.as_micros() as f64 / (*stored_micros).max(1) as f64 - 1.0);
+                    format!("{:+.0}%", change)
+                }
+                (Some(_), None) => "new".to_string(),
+                (None, _) => String::new(),
+            }
+        }
+    }
+}
+
+/// Returns the longest execution time, with some headroom when it is short, used as the full width of the bars.
+pub fn longest_duration<'a>(results: impl Iterator<Item = &'a Option<TestRunResult>>) -> Duration {
+    let mut longest_duration = results
+        .filter_map(|value| value.as_ref())
+        .map(|result| result.elapsed)
+        .max().unwrap_or(Duration::from_secs(0));
+
+    if longest_duration.as_secs() < 5 {
+        longest_duration *= 6;
+        longest_duration /= 5;
+    }
+
+    longest_duration
+}
+
+pub fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Prints a line based diff of the two texts, showing only the changed lines.
+fn print_diff(path: &Path, old: &str, new: &str) {
+    let old_lines = old.split('\n').collect::<Vec<_>>();
+    let new_lines = new.split('\n').collect::<Vec<_>>();
+
+    let mut lcs = vec![vec![0usize; new_lines.len() + 1]; old_lines.len() + 1];
+    for i in (0..old_lines.len()).rev() {
+        for j in (0..new_lines.len()).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    println!("{}", format!("--- {}", path.display()).red().bold());
+    println!("{}", format!("+++ {}", path.display()).green().bold());
+
+    let (mut i, mut j) = (0, 0);
+    let mut in_hunk = false;
+    while i < old_lines.len() || j < new_lines.len() {
+        if i < old_lines.len() && j < new_lines.len() && old_lines[i] == new_lines[j] {
+            (i, j, in_hunk) = (i + 1, j + 1, false);
+            continue;
+        }
+
+        if !in_hunk {
+            println!("{}", format!("@@ line {} @@", i + 1).cyan());
+            in_hunk = true;
+        }
+        if i < old_lines.len() && (j == new_lines.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            println!("{}", format!("-{}", old_lines[i]).red());
+            i += 1;
+        } else {
+            println!("{}", format!("+{}", new_lines[j]).green());
+            j += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::results_block::ResultsBlock;
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    fn result(micros: u64, part1_micros: Option<u64>) -> Option<TestRunResult> {
+        Some(TestRunResult {
+            test_name: "Test 1".to_string(),
+            elapsed: Duration::from_micros(micros),
+            part1_elapsed: part1_micros.map(Duration::from_micros),
+            part2_elapsed: part1_micros.map(|part1| Duration::from_micros(micros - part1)),
+            part1_result: String::new(),
+            part2_result: String::new(),
+            part1_success: Some(true),
+            part2_success: Some(true),
+            alloc_stats: None,
+        })
+    }
+
+    fn config() -> ReportConfig {
+        let fingerprint = Fingerprint {
+            cpu_model: "Test CPU".to_string(),
+            cores: 4,
+            os: "Test OS".to_string(),
+            rustc_version: "rustc 1.0.0".to_string(),
+            profile: "release".to_string(),
+        };
+        ReportConfig { style: ReportStyle::Unicode, fingerprint, ..ReportConfig::new(2025) }
+    }
+
+    fn block(content: &str) -> ResultsBlock {
+        parse_blocks(content).unwrap().remove(0)
+    }
+
+    #[test]
+    fn parses_block_options() {
+        let options = BlockOptions::parse("<!-- {RESULTS_START columns=day,part1,delta format=list style=svg year=2024 threshold=2.5 source=x/{day}.rs} -->").unwrap();
+        assert_eq!(options.columns, [ReportColumn::Day, ReportColumn::Part1, ReportColumn::Delta]);
+        assert_eq!((options.format, options.style, options.year), (ReportFormat::List, Some(ReportStyle::LocalSvg), Some(2024)));
+        assert_eq!((options.threshold, options.source.as_str()), (2.5, "x/{day}.rs"));
+
+        let options = BlockOptions::parse("<!-- {RESULTS_START} -->").unwrap();
+        assert_eq!(options.columns, BlockOptions::default().columns);
+        assert_eq!((options.style, options.year, options.threshold), (None, None, DEFAULT_THRESHOLD));
+    }
+
+    #[test]
+    fn rejects_invalid_block_options() {
+        let error = |options: &str| BlockOptions::parse(&format!("<!-- {{RESULTS_START {}}} -->", options)).unwrap_err();
+        assert_eq!(error("columns=day,time"), "Unknown report column 'time'");
+        assert_eq!(error("format=grid"), "Unknown report format 'grid'");
+        assert_eq!(error("style=png"), "Unknown report style 'png'");
+        assert_eq!(error("year=next"), "Invalid report year 'next'");
+        assert_eq!(error("threshold=high"), "Invalid report threshold 'high'");
+        assert_eq!(error("colour=red"), "Unknown report option 'colour'");
+        assert_eq!(error("table"), "Invalid report option 'table', expected key=value");
+        assert_eq!(BlockOptions::parse("<!-- {RESULTS_START").unwrap_err(), "Unterminated results marker");
+    }
+
+    #[test]
+    fn renders_a_table() {
+        let (day1, day2) = (result(56, Some(20)), result(31355, None));
+        let data = ReportData { year: 2025, days: vec![
+            DayReport { day: 1, title: "Secret Entrance", result: &day1 },
+            DayReport { day: 2, title: "Gift Shop", result: &day2 },
+            DayReport { day: 3, title: "Lobby", result: &None },
+        ] };
+        let content = "<!-- {RESULTS_START columns=day,title,status,part1,part2,total,delta} -->\n<!-- {DAY 1=50} -->\n<!-- {RESULTS_END} -->\n";
+
+        let (lines, changed) = config().render_block(&block(content), &data, &mut Vec::new());
+        assert!(changed);
+        assert_eq!(lines[..5], [
+            "| Day | Title | Status | Part 1 | Part 2 | Execution Time | Change |",
+            "|-----|-------|--------|--------|--------|----------------|--------|",
+            "|[1](src/days/day1.rs)|Secret Entrance|✅ Success|20µs|36µs|56µs|+12%|",
+            "|[2](src/days/day2.rs)|Gift Shop|✅ Success|-|-|31ms|new|",
+            "|[3](src/days/day3.rs)|Lobby|❔ Inconclusive|||||",
+        ]);
+        assert_eq!(lines[5..8], ["<!-- {DAY 1=56} -->", "<!-- {DAY 2=31355} -->", format!("<!-- {{DAY 3={}}} -->", u128::MAX).as_str()]);
+        assert!(lines[8].starts_with("<!-- {LAYOUT "));
+        assert_eq!(lines[9], format!("<!-- {{MACHINE {} {}}} -->", config().fingerprint.id(), config().fingerprint.summary()));
+    }
+
+    #[test]
+    fn renders_a_list_with_bars() {
+        let day1 = result(1000, None);
+        let data = ReportData { year: 2024, days: vec![DayReport { day: 1, title: "Historian Hysteria", result: &day1 }] };
+        let content = "<!-- {RESULTS_START columns=day,bar,total format=list style=svg source={year}/{day}} -->\n<!-- {RESULTS_END} -->\n";
+
+        let mut assets = Vec::new();
+        let (lines, _) = config().render_block(&block(content), &data, &mut assets);
+        assert_eq!(lines[0], "- [Day 1](2024/1) · ![83%](docs/bars/2024/day1.svg) · 1ms");
+        assert_eq!(assets.iter().map(|asset| asset.path.as_str()).collect::<Vec<_>>(), ["docs/bars/2024/day1.svg"]);
+    }
+
+    #[test]
+    fn only_rewrites_past_the_threshold() {
+        let content = |micros: u64| {
+            let day1 = result(micros, None);
+            let data = ReportData { year: 2025, days: vec![DayReport { day: 1, title: "", result: &day1 }] };
+            let (lines, _) = config().render_block(&block("<!-- {RESULTS_START} -->\n<!-- {RESULTS_END} -->\n"), &data, &mut Vec::new());
+            format!("<!-- {{RESULTS_START}} -->\n{}\n<!-- {{RESULTS_END}} -->\n", lines.join("\n"))
+        };
+        let stored = content(1000);
+
+        let render = |micros: u64| {
+            let day1 = result(micros, None);
+            let data = ReportData { year: 2025, days: vec![DayReport { day: 1, title: "", result: &day1 }] };
+            config().render_block(&block(&stored), &data, &mut Vec::new()).1
+        };
+        assert!(!render(1000));
+        assert!(!render(1095));
+        assert!(!render(910));
+        assert!(render(1112));
+        assert!(render(880));
+    }
+
+    #[test]
+    fn writes_only_blocks_of_the_year_and_machine() {
+        let dir = TestDir::new("report");
+        let path = dir.join("README.md");
+        let other_machine = "<!-- {RESULTS_START year=2025} -->\nold\n<!-- {MACHINE 0000abcd Other CPU} -->\n<!-- {RESULTS_END} -->\n";
+        let content = format!("# Title\n<!-- {{RESULTS_START style=svg}} -->\nold\n<!-- {{RESULTS_END}} -->\n{}<!-- {{RESULTS_START year=2024}} -->\nold\n<!-- {{RESULTS_END}} -->\n", other_machine);
+        std::fs::write(&path, &content).unwrap();
+
+        let day1 = result(56, None);
+        let data = ReportData { year: 2025, days: vec![DayReport { day: 1, title: "", result: &day1 }] };
+        config().write_file(&path, &data).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Title\n<!-- {RESULTS_START style=svg} -->\n| Day |"));
+        assert!(written.contains(other_machine));
+        assert!(written.ends_with("<!-- {RESULTS_START year=2024} -->\nold\n<!-- {RESULTS_END} -->\n"));
+        assert!(dir.join("docs/badges/success.svg").exists());
+        assert!(dir.join("docs/bars/2025/day1.svg").exists());
+
+        let forced = ReportConfig { force: true, ..config() };
+        forced.write_file(&path, &data).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("Other CPU"));
+    }
+
+    #[test]
+    fn timings_without_a_machine_are_only_replaced_with_force() {
+        let dir = TestDir::new("report-unknown-machine");
+        let path = dir.join("README.md");
+        let content = "<!-- {RESULTS_START} -->\nold\n<!-- {DAY 1=56} -->\n<!-- {RESULTS_END} -->\n";
+        std::fs::write(&path, content).unwrap();
+
+        let day1 = result(80, None);
+        let data = ReportData { year: 2025, days: vec![DayReport { day: 1, title: "", result: &day1 }] };
+        config().write_file(&path, &data).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+
+        ReportConfig { force: true, ..config() }.write_file(&path, &data).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("<!-- {DAY 1=80} -->"));
+        assert!(written.contains(&format!("<!-- {{MACHINE {} ", config().fingerprint.id())));
+    }
+
+    #[test]
+    fn dry_run_leaves_the_file_alone() {
+        let dir = TestDir::new("report-dry-run");
+        let path = dir.join("README.md");
+        std::fs::write(&path, "<!-- {RESULTS_START} -->\n<!-- {RESULTS_END} -->\n").unwrap();
+
+        let day1 = result(56, None);
+        let data = ReportData { year: 2025, days: vec![DayReport { day: 1, title: "", result: &day1 }] };
+        ReportConfig { dry_run: true, ..config() }.write(&data);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "<!-- {RESULTS_START} -->\n<!-- {RESULTS_END} -->\n");
+    }
+
+    #[test]
+    fn longest_duration_has_headroom_when_short() {
+        assert_eq!(longest_duration([result(500, None), None, result(1000, None)].iter()), Duration::from_micros(1200));
+        assert_eq!(longest_duration([result(6_000_000, None)].iter()), Duration::from_secs(6));
+        assert_eq!(longest_duration([None].iter()), Duration::ZERO);
+    }
+
+    #[test]
+    fn fnv1a_hash() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+}
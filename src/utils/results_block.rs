@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::utils::report::BlockOptions;
+
+pub const BLOCK_START: &str = "<!-- {RESULTS_START";
+pub const BLOCK_END: &str = "<!-- {RESULTS_END}";
+const DAY_PREFIX: &str = "<!-- {DAY ";
+const LAYOUT_PREFIX: &str = "<!-- {LAYOUT ";
+const MACHINE_PREFIX: &str = "<!-- {MACHINE ";
+const COMMENT_SUFFIX: &str = "} -->";
+
+#[derive(Debug)]
+pub enum ReportError {
+    Io { path: PathBuf, error: std::io::Error },
+    Parse { line: usize, message: String },
+}
+
+impl Display for ReportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ReportError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+/// A results block found in a document. The body is the text between the start and end
+/// marker lines, located by byte offsets so that everything around it can be kept as is.
+#[derive(Debug)]
+pub struct ResultsBlock {
+    pub options: BlockOptions,
+    pub body_start: usize,
+    pub body_end: usize,
+    pub line_ending: &'static str,
+    /// Execution times in microseconds stored by the previous rewrite, `u128::MAX` for days without a result.
+    pub baseline: HashMap<u8, u128>,
+    pub layout: Option<String>,
+    /// Fingerprint id and summary of the machine that measured the stored timings.
+    pub machine: Option<(String, String)>,
+    pub start_line: usize,
+}
+
+/// Finds every results block in `content` and parses its options and stored timings.
+pub fn parse_blocks(content: &str) -> Result<Vec<ResultsBlock>, ReportError> {
+    let mut blocks = Vec::new();
+    let mut current: Option<ResultsBlock> = None;
+    let mut offset = 0;
+
+    for (index, raw_line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        let parse_error = |message: String| ReportError::Parse { line: line_number, message };
+
+        if line.starts_with(BLOCK_START) {
+            if let Some(block) = &current {
+                return Err(parse_error(format!("Results block started before the block on line {} was closed", block.start_line)));
+            }
+
+            let options = BlockOptions::parse(line).map_err(parse_error)?;
+            let line_ending = if raw_line.ends_with("\r\n") { "\r\n" } else { "\n" };
+            current = Some(ResultsBlock {
+                options,
+                body_start: offset + raw_line.len(),
+                body_end: 0,
+                line_ending,
+                baseline: HashMap::new(),
+                layout: None,
+                machine: None,
+                start_line: line_number,
+            });
+        } else if line.starts_with(BLOCK_END) {
+            let Some(mut block) = current.take() else {
+                return Err(parse_error("Results block closed without being started".to_string()));
+            };
+            block.body_end = offset;
+            blocks.push(block);
+        } else if let Some(block) = current.as_mut() {
+            if line.starts_with(DAY_PREFIX) {
+                let (day, micros) = parse_day_comment(line).map_err(parse_error)?;
+                if block.baseline.insert(day, micros).is_some() {
+                    return Err(parse_error(format!("Duplicate timing for day {}", day)));
+                }
+            } else if line.starts_with(LAYOUT_PREFIX) {
+                block.layout = Some(parse_layout_comment(line).map_err(parse_error)?);
+            } else if line.starts_with(MACHINE_PREFIX) {
+                block.machine = Some(parse_machine_comment(line).map_err(parse_error)?);
+            }
+        }
+
+        offset += raw_line.len();
+    }
+
+    if let Some(block) = current {
+        return Err(ReportError::Parse { line: block.start_line, message: "Results block is never closed".to_string() });
+    }
+
+    Ok(blocks)
+}
+
+/// Parses `<!-- {DAY n=micros} -->`.
+fn parse_day_comment(line: &str) -> Result<(u8, u128), String> {
+    let invalid = || format!("Invalid timing comment '{}', expected <!-- {{DAY n=micros}} -->", line);
+
+    let inner = line.strip_prefix(DAY_PREFIX)
+        .and_then(|rest| rest.strip_suffix(COMMENT_SUFFIX))
+        .ok_or_else(invalid)?;
+    let (day, micros) = inner.split_once('=').ok_or_else(invalid)?;
+    let day = day.trim().parse::<u8>().map_err(|_| invalid())?;
+    let micros = micros.trim().parse::<u128>().map_err(|_| invalid())?;
+
+    Ok((day, micros))
+}
+
+/// Parses `<!-- {LAYOUT hash} -->`.
+fn parse_layout_comment(line: &str) -> Result<String, String> {
+    line.strip_prefix(LAYOUT_PREFIX)
+        .and_then(|rest| rest.strip_suffix(COMMENT_SUFFIX))
+        .map(|hash| hash.trim())
+        .filter(|hash| !hash.is_empty() && hash.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .map(|hash| hash.to_string())
+        .ok_or_else(|| format!("Invalid layout comment '{}', expected <!-- {{LAYOUT hash}} -->", line))
+}
+
+/// Parses `<!-- {MACHINE id summary} -->`.
+fn parse_machine_comment(line: &str) -> Result<(String, String), String> {
+    line.strip_prefix(MACHINE_PREFIX)
+        .and_then(|rest| rest.strip_suffix(COMMENT_SUFFIX))
+        .and_then(|inner| inner.trim().split_once(' '))
+        .map(|(id, summary)| (id.to_string(), summary.trim().to_string()))
+        .ok_or_else(|| format!("Invalid machine comment '{}', expected <!-- {{MACHINE id summary}} -->", line))
+}
+
+/// Returns `content` with the body of every block replaced by the matching entry of `bodies`,
+/// leaving all bytes outside the bodies untouched.
+pub fn replace_bodies(content: &str, blocks: &[ResultsBlock], bodies: &[Option<String>]) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut offset = 0;
+    for (block, body) in blocks.iter().zip(bodies) {
+        let Some(body) = body else {
+            continue;
+        };
+        output.push_str(&content[offset..block.body_start]);
+        output.push_str(body);
+        offset = block.body_end;
+    }
+    output.push_str(&content[offset..]);
+
+    output
+}
+
+/// Writes the file through a temporary file in the same folder, so that an interrupted
+/// write never leaves a truncated file behind.
+pub fn write_atomic(path: &std::path::Path, content: &str) -> Result<(), ReportError> {
+    let io_error = |error| ReportError::Io { path: path.to_path_buf(), error };
+
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("report");
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+
+    let mut file = std::fs::File::create(&temp_path).map_err(io_error)?;
+    std::io::Write::write_all(&mut file, content.as_bytes()).map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+    drop(file);
+
+    std::fs::rename(&temp_path, path).map_err(|error| {
+        _ = std::fs::remove_file(&temp_path);
+        io_error(error)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    const README: &str = "# Title\n<!-- {RESULTS_START year=2024} -->\n| Day |\n<!-- {DAY 1=56} -->\n<!-- {DAY 2=340282366920938463463374607431768211455} -->\n<!-- {LAYOUT 00ff00ff00ff00ff} -->\n<!-- {MACHINE 1a2b3c4d Test CPU (4 threads)} -->\n<!-- {RESULTS_END} -->\nText\n<!-- {RESULTS_START} -->\n<!-- {RESULTS_END} -->\n";
+
+    fn parse_error(content: &str) -> (usize, String) {
+        match parse_blocks(content) {
+            Err(ReportError::Parse { line, message }) => (line, message),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_blocks() {
+        let blocks = parse_blocks(README).unwrap();
+        assert_eq!(blocks.len(), 2);
+
+        let first = &blocks[0];
+        assert_eq!((first.start_line, first.options.year, first.line_ending), (2, Some(2024), "\n"));
+        assert!(README[first.body_start..first.body_end].starts_with("| Day |\n<!-- {DAY 1=56} -->\n"));
+        assert!(README[first.body_start..first.body_end].ends_with("Test CPU (4 threads)} -->\n"));
+        assert_eq!(first.baseline, HashMap::from([(1, 56), (2, u128::MAX)]));
+        assert_eq!(first.layout.as_deref(), Some("00ff00ff00ff00ff"));
+        assert_eq!(first.machine, Some(("1a2b3c4d".to_string(), "Test CPU (4 threads)".to_string())));
+
+        let second = &blocks[1];
+        assert_eq!((second.start_line, second.body_start), (10, second.body_end));
+        assert!(second.baseline.is_empty() && second.layout.is_none() && second.machine.is_none());
+    }
+
+    #[test]
+    fn parses_crlf_input() {
+        let content = README.replace('\n', "\r\n");
+        let blocks = parse_blocks(&content).unwrap();
+        assert_eq!(blocks[0].line_ending, "\r\n");
+        assert_eq!(blocks[0].baseline.get(&1), Some(&56));
+        assert_eq!(blocks[0].layout.as_deref(), Some("00ff00ff00ff00ff"));
+        assert!(content[blocks[0].body_start..].starts_with("| Day |\r\n"));
+        assert!(content[blocks[0].body_end..].starts_with("<!-- {RESULTS_END} -->\r\n"));
+    }
+
+    #[test]
+    fn rejects_malformed_comments() {
+        let block = |comment: &str| format!("<!-- {{RESULTS_START}} -->\n{}\n<!-- {{RESULTS_END}} -->\n", comment);
+
+        for comment in ["<!-- {DAY 1} -->", "<!-- {DAY x=5} -->", "<!-- {DAY 1=-5} -->", "<!-- {DAY 1=5 -->", "<!-- {DAY 300=5} -->"] {
+            let (line, message) = parse_error(&block(comment));
+            assert_eq!(line, 2);
+            assert!(message.starts_with("Invalid timing comment"), "{}", message);
+        }
+        for comment in ["<!-- {LAYOUT } -->", "<!-- {LAYOUT xyz} -->", "<!-- {LAYOUT 00ff -->"] {
+            let (line, message) = parse_error(&block(comment));
+            assert_eq!(line, 2);
+            assert!(message.starts_with("Invalid layout comment"), "{}", message);
+        }
+        let (_, message) = parse_error(&block("<!-- {MACHINE 1a2b3c4d} -->"));
+        assert!(message.starts_with("Invalid machine comment"), "{}", message);
+        assert_eq!(parse_error(&block("<!-- {DAY 1=5} -->\n<!-- {DAY 1=6} -->")), (3, "Duplicate timing for day 1".to_string()));
+    }
+
+    #[test]
+    fn ignores_comments_outside_of_blocks() {
+        assert!(parse_blocks("<!-- {DAY x} -->\n<!-- {LAYOUT ?} -->\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_unbalanced_markers() {
+        assert_eq!(parse_error("a\n<!-- {RESULTS_START} -->\n| Day |\n"), (2, "Results block is never closed".to_string()));
+        assert_eq!(parse_error("<!-- {RESULTS_START} -->\n<!-- {RESULTS_START} -->\n"),
+                   (2, "Results block started before the block on line 1 was closed".to_string()));
+        assert_eq!(parse_error("<!-- {RESULTS_END} -->\n"), (1, "Results block closed without being started".to_string()));
+        assert_eq!(parse_error("<!-- {RESULTS_START format=grid} -->\n"), (1, "Unknown report format 'grid'".to_string()));
+    }
+
+    #[test]
+    fn replace_bodies_keeps_everything_else() {
+        let blocks = parse_blocks(README).unwrap();
+        assert_eq!(replace_bodies(README, &blocks, &[None, None]), README);
+
+        let unchanged = blocks.iter().map(|block| Some(README[block.body_start..block.body_end].to_string())).collect::<Vec<_>>();
+        assert_eq!(replace_bodies(README, &blocks, &unchanged), README);
+
+        let replaced = replace_bodies(README, &blocks, &[None, Some("new\n".to_string())]);
+        assert_eq!(replaced, README.replace("<!-- {RESULTS_START} -->\n", "<!-- {RESULTS_START} -->\nnew\n"));
+
+        let crlf = README.replace('\n', "\r\n");
+        let blocks = parse_blocks(&crlf).unwrap();
+        let unchanged = blocks.iter().map(|block| Some(crlf[block.body_start..block.body_end].to_string())).collect::<Vec<_>>();
+        assert_eq!(replace_bodies(&crlf, &blocks, &unchanged), crlf);
+    }
+
+    #[test]
+    fn write_atomic_renames_the_temp_file() {
+        let dir = TestDir::new("write-atomic");
+        let path = dir.join("docs/README.md");
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        let files = std::fs::read_dir(dir.join("docs")).unwrap().map(|entry| entry.unwrap().file_name()).collect::<Vec<_>>();
+        assert_eq!(files, ["README.md"]);
+    }
+
+    #[test]
+    fn write_atomic_reports_the_path() {
+        let dir = TestDir::new("write-atomic-error");
+        std::fs::create_dir(dir.join("README.md")).unwrap();
+
+        match write_atomic(&dir.join("README.md"), "text") {
+            Err(ReportError::Io { path, .. }) => assert_eq!(path, dir.join("README.md")),
+            other => panic!("Expected an io error, got {:?}", other),
+        }
+        assert!(!dir.join(".README.md.tmp").exists());
+    }
+}
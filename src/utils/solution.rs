@@ -1,8 +1,8 @@
 use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use crate::utils::test_set::{TestRunResult, TestSet};
 
 #[derive(Debug, Copy, Clone)]
-#[allow(dead_code)]
 pub enum SolveTest {
     All,
     Last,
@@ -17,17 +17,44 @@ macro_rules! solution {
 
 pub(crate) use solution;
 
+/// The year whose files were kept directly in `data` before the files were split by year.
+const LEGACY_YEAR: i32 = 2025;
+
+fn data_root() -> PathBuf {
+    std::env::current_dir().unwrap().join("data")
+}
+
+/// Returns the folder holding the test files of the given year, `data/<year>`.
+pub fn data_path(year: i32) -> PathBuf {
+    data_root().join(year.to_string())
+}
+
+/// Returns the path of a data file of the given year, see `data_file_in`.
+pub fn data_file(year: i32, name: &str) -> PathBuf {
+    data_file_in(&data_root(), year, name)
+}
+
+/// Returns `<root>/<year>/<name>`. For 2025 the file is still read from `<root>/<name>` when only
+/// that exists, so checkouts from before the files were split by year keep their inputs.
+pub fn data_file_in(root: &Path, year: i32, name: &str) -> PathBuf {
+    let path = root.join(year.to_string()).join(name);
+    let legacy_path = root.join(name);
+    if year == LEGACY_YEAR && !path.exists() && legacy_path.exists() {
+        legacy_path
+    } else {
+        path
+    }
+}
+
 pub trait Solution {
     fn solve(&self, input: String) -> (Box<dyn Display>, Box<dyn Display>);
 
-    fn solve_test(&self, day: u8, solve_test: SolveTest) -> Option<TestRunResult> {
-        let exe_path = std::env::current_dir().unwrap();
-        let data_path = exe_path.join("data");
-        if !data_path.exists() {
-            panic!("No data folder found at {}", data_path.to_str().unwrap());
-        }
+    fn title(&self) -> &str {
+        ""
+    }
 
-        let input_path = data_path.join(format!("day{}.test", day));
+    fn solve_test(&self, year: i32, day: u8, solve_test: SolveTest) -> Option<TestRunResult> {
+        let input_path = data_file(year, &format!("day{}.test", day));
         if !input_path.exists() {
             panic!("No test found at {}", input_path.to_str().unwrap());
         }
@@ -41,4 +68,29 @@ pub trait Solution {
             SolveTest::Last => Some(test_set.test_one(test_set.len() - 1, |input| self.solve(input))),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    #[test]
+    fn data_files_are_split_by_year() {
+        let dir = TestDir::new("data");
+        assert_eq!(data_file_in(dir.path(), 2024, "day1.test"), dir.join("2024/day1.test"));
+        assert_eq!(data_file_in(dir.path(), 2025, "day1.test"), dir.join("2025/day1.test"));
+    }
+
+    #[test]
+    fn legacy_files_are_read_for_2025_only() {
+        let dir = TestDir::new("data");
+        std::fs::write(dir.join("day1.test"), "@test\n").unwrap();
+        assert_eq!(data_file_in(dir.path(), 2025, "day1.test"), dir.join("day1.test"));
+        assert_eq!(data_file_in(dir.path(), 2024, "day1.test"), dir.join("2024/day1.test"));
+
+        std::fs::create_dir(dir.join("2025")).unwrap();
+        std::fs::write(dir.join("2025/day1.test"), "@test\n").unwrap();
+        assert_eq!(data_file_in(dir.path(), 2025, "day1.test"), dir.join("2025/day1.test"));
+    }
+}
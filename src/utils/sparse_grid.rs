@@ -0,0 +1,256 @@
+use ahash::{AHashMap, AHashSet};
+use crate::utils::grid;
+use crate::utils::matrix::{Connectivity, Matrix};
+
+/// The smallest rectangle containing every cell of a `SparseGrid`, both corners included.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+}
+
+/// An unbounded grid keyed by signed coordinates that only stores the cells that were set,
+/// for grids that keep growing or are mostly empty.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: AHashMap<(i64, i64), T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self { cells: AHashMap::new() }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        self.cells.get(&(x, y))
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        self.cells.get_mut(&(x, y))
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.cells.contains_key(&(x, y))
+    }
+
+    /// Sets the cell and returns its previous value.
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        self.cells.insert((x, y), value)
+    }
+
+    pub fn remove(&mut self, x: i64, y: i64) -> Option<T> {
+        self.cells.remove(&(x, y))
+    }
+
+    /// All set cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.cells.iter().map(|(position, cell)| (*position, cell))
+    }
+
+    pub fn count<F>(&self, f: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.cells.values().filter(|cell| f(cell)).count()
+    }
+
+    /// The smallest rectangle containing every set cell, `None` for an empty grid.
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut cells = self.cells.keys();
+        let &(x, y) = cells.next()?;
+        let bounds = Bounds { min_x: x, min_y: y, max_x: x, max_y: y };
+
+        Some(cells.fold(bounds, |bounds, &(x, y)| Bounds {
+            min_x: bounds.min_x.min(x),
+            min_y: bounds.min_y.min(y),
+            max_x: bounds.max_x.max(x),
+            max_y: bounds.max_y.max(y),
+        }))
+    }
+
+    /// The set orthogonal neighbours of `(x, y)`, clockwise starting north.
+    pub fn neighbors4(&self, x: i64, y: i64) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.neighbors(x, y, Connectivity::Four)
+    }
+
+    /// The set orthogonal and diagonal neighbours of `(x, y)`, clockwise starting north.
+    pub fn neighbors8(&self, x: i64, y: i64) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.neighbors(x, y, Connectivity::Eight)
+    }
+
+    pub fn neighbors(&self, x: i64, y: i64, connectivity: Connectivity) -> impl Iterator<Item = ((i64, i64), &T)> {
+        connectivity.offsets().iter().filter_map(move |&(dx, dy)| {
+            let position = (x + dx as i64, y + dy as i64);
+            self.cells.get(&position).map(|cell| (position, cell))
+        })
+    }
+
+    /// `grid::flood_where` from `(x, y)`. Unset cells are never filled, so the fill always ends.
+    pub fn flood_where<F>(&self, x: i64, y: i64, connectivity: Connectivity, f: F) -> AHashSet<(i64, i64)>
+    where
+        F: FnMut(&(i64, i64), &T, &(i64, i64), &T) -> bool,
+    {
+        grid::flood_where(self, (x, y), connectivity, f)
+    }
+
+    /// Fills the set cells equal to the one at `(x, y)`.
+    pub fn flood_eq(&self, x: i64, y: i64, connectivity: Connectivity) -> AHashSet<(i64, i64)>
+    where
+        T: PartialEq,
+    {
+        self.flood_where(x, y, connectivity, |_, from, _, to| from == to)
+    }
+
+    /// Copies the cells of `matrix` that match the predicate, keeping their coordinates.
+    pub fn from_matrix(matrix: &Matrix<T>, mut keep: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        let mut grid = Self::new();
+        for y in 0..matrix.height() {
+            for (position, cell) in matrix.row_cells(y) {
+                if keep(cell) {
+                    grid.set(position.x as i64, position.y as i64, cell.clone());
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Copies the bounding box into a `Matrix`, filling unset cells with `empty`. Also returns the
+    /// coordinates of the top left cell, as the matrix always starts at `(0, 0)`.
+    pub fn to_matrix(&self, empty: T) -> (Matrix<T>, (i64, i64))
+    where
+        T: Clone,
+    {
+        let Some(bounds) = self.bounds() else {
+            return (Matrix::new_fill(0, 0, empty), (0, 0));
+        };
+
+        let mut matrix = Matrix::new_fill(bounds.width(), bounds.height(), empty);
+        for (&(x, y), cell) in &self.cells {
+            matrix[((x - bounds.min_x) as usize, (y - bounds.min_y) as usize)] = cell.clone();
+        }
+
+        (matrix, (bounds.min_x, bounds.min_y))
+    }
+
+    /// Renders the bounding box one character per cell, `f` receives `None` for unset cells.
+    pub fn render(&self, mut f: impl FnMut(Option<&T>) -> char) -> String {
+        let Some(bounds) = self.bounds() else {
+            return String::new();
+        };
+
+        (bounds.min_y..=bounds.max_y)
+            .map(|y| (bounds.min_x..=bounds.max_x).map(|x| f(self.get(x, y))).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plus sign of `#` around the origin with a `.` to the far right.
+    fn plus() -> SparseGrid<char> {
+        let mut grid = SparseGrid::new();
+        for (x, y) in [(0, -1), (-1, 0), (0, 0), (1, 0), (0, 1)] {
+            grid.set(x, y, '#');
+        }
+        grid.set(3, 0, '.');
+        grid
+    }
+
+    #[test]
+    fn sets_and_removes_cells() {
+        let mut grid = plus();
+        assert_eq!((grid.len(), grid.get(-1, 0), grid.get(1, 1)), (6, Some(&'#'), None));
+        assert_eq!(grid.set(3, 0, '#'), Some('.'));
+        *grid.get_mut(3, 0).unwrap() = 'x';
+        assert_eq!(grid.remove(3, 0), Some('x'));
+        assert_eq!(grid.remove(3, 0), None);
+        assert!(!grid.contains(3, 0));
+        assert_eq!(grid.count(|cell| *cell == '#'), 5);
+        assert_eq!(grid.iter().count(), 5);
+        assert!(SparseGrid::<u8>::new().is_empty());
+    }
+
+    #[test]
+    fn bounds_cover_every_cell() {
+        let bounds = plus().bounds().unwrap();
+        assert_eq!(bounds, Bounds { min_x: -1, min_y: -1, max_x: 3, max_y: 1 });
+        assert_eq!((bounds.width(), bounds.height()), (5, 3));
+        assert!(bounds.contains(3, -1));
+        assert!(!bounds.contains(-2, 0));
+        assert_eq!(SparseGrid::<u8>::new().bounds(), None);
+    }
+
+    #[test]
+    fn neighbors_are_set_cells() {
+        let grid = plus();
+        let positions = |cells: Vec<((i64, i64), &char)>| cells.into_iter().map(|(position, _)| position).collect::<Vec<_>>();
+        assert_eq!(positions(grid.neighbors4(0, 0).collect()), [(0, -1), (1, 0), (0, 1), (-1, 0)]);
+        assert_eq!(positions(grid.neighbors8(1, 1).collect()), [(1, 0), (0, 1), (0, 0)]);
+        assert_eq!(positions(grid.neighbors(-5, -5, Connectivity::Eight).collect()), []);
+    }
+
+    #[test]
+    fn flood_stays_on_set_cells() {
+        let grid = plus();
+        assert_eq!(grid.flood_eq(0, 0, Connectivity::Four).len(), 5);
+        assert_eq!(grid.flood_eq(3, 0, Connectivity::Eight), AHashSet::from([(3, 0)]));
+        assert!(grid.flood_eq(2, 0, Connectivity::Four).is_empty());
+        assert_eq!(grid.flood_where(0, -1, Connectivity::Eight, |_, _, to, _| to.1 < 1).len(), 4);
+    }
+
+    #[test]
+    fn converts_to_and_from_matrices() {
+        let (matrix, origin) = plus().to_matrix(' ');
+        assert_eq!(origin, (-1, -1));
+        assert_eq!(matrix.row(1), ['#', '#', '#', ' ', '.']);
+
+        let grid = SparseGrid::from_matrix(&matrix, |cell| *cell != ' ');
+        assert_eq!((grid.len(), grid.get(4, 1), grid.get(0, 0)), (6, Some(&'.'), None));
+
+        let (empty, origin) = SparseGrid::<char>::new().to_matrix(' ');
+        assert_eq!((empty.width(), empty.height(), origin), (0, 0, (0, 0)));
+    }
+
+    #[test]
+    fn renders_the_bounds() {
+        assert_eq!(plus().render(|cell| cell.copied().unwrap_or(' ')), " #   \n### .\n #   ");
+        assert_eq!(SparseGrid::<char>::new().render(|_| '#'), "");
+    }
+}
@@ -0,0 +1,618 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use crate::utils::solution::data_file_in;
+use crate::utils::test_set::TestSet;
+
+const DEFAULT_ENDPOINT: &str = "https://adventofcode.com";
+const SESSION_FILE_NAME: &str = ".aoc-session";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SubmitOutcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    Wait(u64),
+    AlreadySolved,
+    Unknown,
+}
+
+impl SubmitOutcome {
+    fn key(&self) -> String {
+        match self {
+            SubmitOutcome::Correct => "correct".to_string(),
+            SubmitOutcome::Wrong => "wrong".to_string(),
+            SubmitOutcome::TooHigh => "too_high".to_string(),
+            SubmitOutcome::TooLow => "too_low".to_string(),
+            SubmitOutcome::Wait(seconds) => format!("wait_{}", seconds),
+            SubmitOutcome::AlreadySolved => "already_solved".to_string(),
+            SubmitOutcome::Unknown => "unknown".to_string(),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "correct" => Some(SubmitOutcome::Correct),
+            "wrong" => Some(SubmitOutcome::Wrong),
+            "too_high" => Some(SubmitOutcome::TooHigh),
+            "too_low" => Some(SubmitOutcome::TooLow),
+            "already_solved" => Some(SubmitOutcome::AlreadySolved),
+            "unknown" => Some(SubmitOutcome::Unknown),
+            _ => key.strip_prefix("wait_")
+                .and_then(|seconds| seconds.parse().ok())
+                .map(SubmitOutcome::Wait),
+        }
+    }
+
+    fn is_rejection(&self) -> bool {
+        matches!(self, SubmitOutcome::Wrong | SubmitOutcome::TooHigh | SubmitOutcome::TooLow)
+    }
+}
+
+impl Display for SubmitOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitOutcome::Correct => write!(f, "That's the right answer!"),
+            SubmitOutcome::Wrong => write!(f, "That's not the right answer."),
+            SubmitOutcome::TooHigh => write!(f, "That's not the right answer, it's too high."),
+            SubmitOutcome::TooLow => write!(f, "That's not the right answer, it's too low."),
+            SubmitOutcome::Wait(seconds) => write!(f, "You gave an answer too recently, wait {}s.", seconds),
+            SubmitOutcome::AlreadySolved => write!(f, "This part has already been solved."),
+            SubmitOutcome::Unknown => write!(f, "The response could not be understood."),
+        }
+    }
+}
+
+/// The parsed answer page returned by the server after a submission.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SubmitResponse {
+    pub outcome: SubmitOutcome,
+    /// Seconds that have to pass before the next answer may be submitted.
+    pub cooldown: u64,
+}
+
+impl SubmitResponse {
+    pub fn parse(html: &str) -> Self {
+        let text = strip_tags(extract_article(html));
+        let lower = text.to_lowercase();
+
+        let outcome = if lower.contains("that's the right answer") {
+            SubmitOutcome::Correct
+        } else if lower.contains("you gave an answer too recently") {
+            SubmitOutcome::Wait(parse_time_left(&lower).unwrap_or(60))
+        } else if lower.contains("that's not the right answer") {
+            if lower.contains("your answer is too high") {
+                SubmitOutcome::TooHigh
+            } else if lower.contains("your answer is too low") {
+                SubmitOutcome::TooLow
+            } else {
+                SubmitOutcome::Wrong
+            }
+        } else if lower.contains("don't seem to be solving the right level") {
+            SubmitOutcome::AlreadySolved
+        } else {
+            SubmitOutcome::Unknown
+        };
+
+        let cooldown = match outcome {
+            SubmitOutcome::Wait(seconds) => seconds,
+            _ => parse_please_wait(&lower).unwrap_or(0),
+        };
+
+        Self { outcome, cooldown }
+    }
+}
+
+fn extract_article(html: &str) -> &str {
+    let start = html.find("<article");
+    let end = html.find("</article>");
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses `You have 1m 5s left to wait` into seconds.
+fn parse_time_left(text: &str) -> Option<u64> {
+    let end = text.find(" left to wait")?;
+    let start = text[..end].rfind("you have ")? + "you have ".len();
+
+    let mut seconds: u64 = 0;
+    for token in text[start..end].split_whitespace() {
+        let (value, unit) = [("h", 3600), ("m", 60), ("s", 1)]
+            .into_iter()
+            .find_map(|(suffix, unit)| token.strip_suffix(suffix).map(|value| (value, unit)))?;
+        seconds = seconds.checked_add(value.parse::<u64>().ok()?.checked_mul(unit)?)?;
+    }
+
+    Some(seconds)
+}
+
+/// Parses `please wait one minute` / `please wait 5 minutes` into seconds.
+fn parse_please_wait(text: &str) -> Option<u64> {
+    let start = text.find("please wait ")? + "please wait ".len();
+    let mut split = text[start..].split_whitespace();
+    let amount = split.next()?;
+    let unit = split.next()?;
+
+    let amount = match amount {
+        "one" | "a" | "an" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "ten" => 10,
+        _ => amount.parse::<u64>().ok()?,
+    };
+
+    if unit.starts_with("minute") {
+        Some(amount * 60)
+    } else if unit.starts_with("second") {
+        Some(amount)
+    } else if unit.starts_with("hour") {
+        Some(amount * 3600)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Guess {
+    pub timestamp: DateTime<Utc>,
+    pub part: u8,
+    pub outcome: SubmitOutcome,
+    pub cooldown: u64,
+    pub answer: String,
+}
+
+/// Reasons for refusing to send an answer without asking the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuessRejection {
+    AlreadySolved(String),
+    AlreadyGuessed(SubmitOutcome),
+    NotBelowUpperBound(String),
+    NotAboveLowerBound(String),
+    RateLimited(u64),
+}
+
+impl Display for GuessRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuessRejection::AlreadySolved(answer) => write!(f, "This part has already been solved with answer {}", answer),
+            GuessRejection::AlreadyGuessed(outcome) => write!(f, "This answer has already been submitted: {}", outcome),
+            GuessRejection::NotBelowUpperBound(bound) => write!(f, "The answer is not lower than {}, which was already too high", bound),
+            GuessRejection::NotAboveLowerBound(bound) => write!(f, "The answer is not higher than {}, which was already too low", bound),
+            GuessRejection::RateLimited(seconds) => write!(f, "Submissions are rate limited for another {}s", seconds),
+        }
+    }
+}
+
+/// Append-only log of every answer submitted for a single day.
+///
+/// Each line has the format `<timestamp> <part> <outcome> <cooldown> <answer>`.
+pub struct GuessHistory {
+    path: PathBuf,
+    guesses: Vec<Guess>,
+}
+
+impl GuessHistory {
+    pub fn load(path: &Path) -> Self {
+        let mut guesses = Vec::new();
+        if let Ok(content) = std::fs::read_to_string(path) {
+            for (line_number, line) in content.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match Self::parse_line(line) {
+                    Some(guess) => guesses.push(guess),
+                    None => println!("{} {} of {}", "Skipping invalid guess on line".yellow(), line_number + 1, path.display()),
+                }
+            }
+        }
+
+        Self { path: path.to_path_buf(), guesses }
+    }
+
+    fn parse_line(line: &str) -> Option<Guess> {
+        let mut split = line.splitn(5, ' ');
+        let timestamp = DateTime::parse_from_rfc3339(split.next()?).ok()?.with_timezone(&Utc);
+        let part = split.next()?.parse().ok()?;
+        let outcome = SubmitOutcome::from_key(split.next()?)?;
+        let cooldown = split.next()?.parse().ok()?;
+        let answer = split.next()?.to_string();
+
+        Some(Guess { timestamp, part, outcome, cooldown, answer })
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    /// Checks a candidate answer against the previous guesses for the same part.
+    pub fn check(&self, part: u8, answer: &str, now: DateTime<Utc>) -> Result<(), GuessRejection> {
+        if let Some(remaining) = self.remaining_cooldown(now) {
+            return Err(GuessRejection::RateLimited(remaining));
+        }
+
+        let part_guesses = self.guesses.iter().filter(|guess| guess.part == part);
+        let numeric_answer = answer.parse::<i128>().ok();
+        for guess in part_guesses {
+            if guess.outcome == SubmitOutcome::Correct {
+                return Err(GuessRejection::AlreadySolved(guess.answer.clone()));
+            }
+            if !guess.outcome.is_rejection() {
+                continue;
+            }
+            if guess.answer == answer {
+                return Err(GuessRejection::AlreadyGuessed(guess.outcome));
+            }
+
+            let (Some(answer), Ok(bound)) = (numeric_answer, guess.answer.parse::<i128>()) else {
+                continue;
+            };
+            match guess.outcome {
+                SubmitOutcome::TooHigh if answer >= bound => {
+                    return Err(GuessRejection::NotBelowUpperBound(guess.answer.clone()));
+                }
+                SubmitOutcome::TooLow if answer <= bound => {
+                    return Err(GuessRejection::NotAboveLowerBound(guess.answer.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of seconds left until the server accepts another answer, if any.
+    pub fn remaining_cooldown(&self, now: DateTime<Utc>) -> Option<u64> {
+        let last = self.guesses.last()?;
+        let unlock = last.timestamp + chrono::Duration::seconds(last.cooldown as i64);
+        let remaining = (unlock - now).num_seconds();
+        if remaining > 0 { Some(remaining as u64) } else { None }
+    }
+
+    pub fn record(&mut self, guess: Guess) {
+        let line = format!("{} {} {} {} {}\n",
+                           guess.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                           guess.part, guess.outcome.key(), guess.cooldown, guess.answer);
+
+        if let Some(parent) = self.path.parent() {
+            _ = std::fs::create_dir_all(parent);
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .unwrap_or_else(|e| panic!("Failed to open {}: {}", self.path.display(), e));
+        std::io::Write::write_all(&mut file, line.as_bytes()).unwrap();
+
+        self.guesses.push(guess);
+    }
+}
+
+/// Connection settings used to talk to the puzzle server.
+///
+/// The endpoint and session are read from the `AOC_ENDPOINT` and `AOC_SESSION` environment
+/// variables, falling back to `https://adventofcode.com` and the contents of `.aoc-session`.
+pub struct SubmitConfig {
+    pub endpoint: String,
+    pub session: String,
+}
+
+impl SubmitConfig {
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Like `from_env`, but looks the variables up with `var`.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let endpoint = var("AOC_ENDPOINT")
+            .unwrap_or(DEFAULT_ENDPOINT.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let session = match var("AOC_SESSION") {
+            Some(session) => session,
+            None => std::fs::read_to_string(SESSION_FILE_NAME)
+                .map_err(|_| format!("No session found, set AOC_SESSION or create {}", SESSION_FILE_NAME))?,
+        };
+
+        Ok(Self { endpoint, session: session.trim().to_string() })
+    }
+
+    pub fn post_answer(&self, year: i32, day: u8, part: u8, answer: &str) -> Result<SubmitResponse, String> {
+        let url = format!("{}/{}/day/{}/answer", self.endpoint, year, day);
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/SparkyTD/aoc-2025")
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| format!("Failed to submit answer to {}: {}", url, e))?;
+        let body = response.into_string()
+            .map_err(|e| format!("Failed to read response from {}: {}", url, e))?;
+
+        Ok(SubmitResponse::parse(&body))
+    }
+}
+
+/// Submits `answer` for the given puzzle part, unless the local guess history already
+/// rules it out, and records the outcome. Correct answers are written back to the last
+/// test of `data/<year>/dayN.test`.
+pub fn submit_answer(year: i32, day: u8, part: u8, answer: &str) {
+    let data_root = std::env::current_dir().unwrap().join("data");
+    submit_answer_in(&data_root, SubmitConfig::from_env, year, day, part, answer);
+}
+
+/// `submit_answer` with the `data` folder and the connection settings passed in. The settings are
+/// only loaded once the guess history allows the submission.
+fn submit_answer_in(data_root: &Path, config: impl FnOnce() -> Result<SubmitConfig, String>, year: i32, day: u8, part: u8, answer: &str) {
+    let mut history = GuessHistory::load(&data_file_in(data_root, year, &format!("day{}.guesses", day)));
+
+    println!("Submitting {} for day {} {}...", answer.bright_blue(), day, format!("part {}", part).bold());
+    if let Err(rejection) = history.check(part, answer, Utc::now()) {
+        println!("{}: {}", "Not submitted".yellow().bold(), rejection);
+        return;
+    }
+
+    let config = match config() {
+        Ok(config) => config,
+        Err(error) => {
+            println!("{}: {}", "Not submitted".red().bold(), error);
+            return;
+        }
+    };
+
+    let response = match config.post_answer(year, day, part, answer) {
+        Ok(response) => response,
+        Err(error) => {
+            println!("{}: {}", "Error".red().bold(), error);
+            return;
+        }
+    };
+
+    history.record(Guess {
+        timestamp: Utc::now(),
+        part,
+        outcome: response.outcome,
+        cooldown: response.cooldown,
+        answer: answer.to_string(),
+    });
+
+    match response.outcome {
+        SubmitOutcome::Correct => {
+            println!("{}", response.outcome.to_string().bright_green().bold());
+            write_answer(&data_file_in(data_root, year, &format!("day{}.test", day)), part, answer);
+        }
+        SubmitOutcome::AlreadySolved | SubmitOutcome::Unknown => println!("{}", response.outcome.to_string().yellow().bold()),
+        _ => println!("{}", response.outcome.to_string().red().bold()),
+    }
+    if response.cooldown > 0 {
+        println!("The next answer can be submitted in {}s.", response.cooldown);
+    }
+}
+
+fn write_answer(test_path: &Path, part: u8, answer: &str) {
+    let raw_input = std::fs::read_to_string(test_path).unwrap();
+    let test_set = TestSet::from(&raw_input);
+    if test_set.is_empty() {
+        return;
+    }
+
+    let output = TestSet::with_answer(&raw_input, test_set.len() - 1, part, answer);
+    std::fs::write(test_path, output).unwrap();
+    println!("Saved the answer to {}", test_path.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use chrono::TimeZone;
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main><article><p>{}</p></article></main></body></html>", message)
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_764_565_200 + seconds, 0).unwrap()
+    }
+
+    fn history(guesses: &[(u8, SubmitOutcome, &str)]) -> GuessHistory {
+        GuessHistory {
+            path: PathBuf::new(),
+            guesses: guesses.iter()
+                .map(|&(part, outcome, answer)| Guess { timestamp: at(0), part, outcome, cooldown: 60, answer: answer.to_string() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_response_kinds() {
+        let cases = [
+            ("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.", SubmitOutcome::Correct, 0),
+            ("That's not the right answer; your answer is too high.  Please wait one minute before trying again.", SubmitOutcome::TooHigh, 60),
+            ("That's not the right answer; your answer is too low.  Please wait 5 minutes before trying again.", SubmitOutcome::TooLow, 300),
+            ("That's not the right answer.  If you're stuck, please wait one minute before trying again.", SubmitOutcome::Wrong, 60),
+            ("You gave an answer too recently; you have to wait.  You have 1m 5s left to wait.", SubmitOutcome::Wait(65), 65),
+            ("You don't seem to be solving the right level.  Did you already complete it?", SubmitOutcome::AlreadySolved, 0),
+            ("Something else entirely.", SubmitOutcome::Unknown, 0),
+        ];
+
+        for (message, outcome, cooldown) in cases {
+            assert_eq!(SubmitResponse::parse(&page(message)), SubmitResponse { outcome, cooldown }, "{}", message);
+        }
+    }
+
+    #[test]
+    fn parse_time_left_rejects_unexpected_tokens() {
+        assert_eq!(parse_time_left("you have 2h 1m 5s left to wait"), Some(7265));
+        assert_eq!(parse_time_left("you have 5é left to wait"), None);
+        assert_eq!(parse_time_left("you have 5 left to wait"), None);
+        assert_eq!(parse_time_left("you have ms left to wait"), None);
+        assert_eq!(parse_time_left("you have 99999999999999999999h left to wait"), None);
+        assert_eq!(parse_time_left("you have 5124095576030432h left to wait"), None);
+        assert_eq!(SubmitResponse::parse(&page("You gave an answer too recently.  You have 1m 5€ left to wait.")).outcome, SubmitOutcome::Wait(60));
+    }
+
+    #[test]
+    fn parse_ignores_text_outside_the_article() {
+        let html = "<nav>That's the right answer</nav><article><p>That's not the right answer.</p></article>";
+        assert_eq!(SubmitResponse::parse(html).outcome, SubmitOutcome::Wrong);
+    }
+
+    #[test]
+    fn outcome_keys_round_trip() {
+        let outcomes = [
+            SubmitOutcome::Correct, SubmitOutcome::Wrong, SubmitOutcome::TooHigh, SubmitOutcome::TooLow,
+            SubmitOutcome::Wait(42), SubmitOutcome::AlreadySolved, SubmitOutcome::Unknown,
+        ];
+        for outcome in outcomes {
+            assert_eq!(SubmitOutcome::from_key(&outcome.key()), Some(outcome));
+        }
+        assert_eq!(SubmitOutcome::from_key("wait_soon"), None);
+    }
+
+    #[test]
+    fn check_uses_too_high_and_too_low_as_bounds() {
+        let history = history(&[(1, SubmitOutcome::TooHigh, "100"), (1, SubmitOutcome::TooLow, "10")]);
+        let later = at(3600);
+
+        assert_eq!(history.check(1, "100", later), Err(GuessRejection::AlreadyGuessed(SubmitOutcome::TooHigh)));
+        assert_eq!(history.check(1, "150", later), Err(GuessRejection::NotBelowUpperBound("100".to_string())));
+        assert_eq!(history.check(1, "5", later), Err(GuessRejection::NotAboveLowerBound("10".to_string())));
+        assert_eq!(history.check(1, "50", later), Ok(()));
+        assert_eq!(history.check(1, "not a number", later), Ok(()));
+        assert_eq!(history.check(2, "150", later), Ok(()));
+    }
+
+    #[test]
+    fn check_rejects_duplicates_and_solved_parts() {
+        let history = history(&[(1, SubmitOutcome::Wrong, "abc"), (2, SubmitOutcome::Correct, "42")]);
+        let later = at(3600);
+
+        assert_eq!(history.check(1, "abc", later), Err(GuessRejection::AlreadyGuessed(SubmitOutcome::Wrong)));
+        assert_eq!(history.check(1, "abd", later), Ok(()));
+        assert_eq!(history.check(2, "43", later), Err(GuessRejection::AlreadySolved("42".to_string())));
+    }
+
+    #[test]
+    fn cooldown_of_the_last_guess() {
+        let history = history(&[(1, SubmitOutcome::Wrong, "1")]);
+
+        assert_eq!(history.remaining_cooldown(at(15)), Some(45));
+        assert_eq!(history.remaining_cooldown(at(60)), None);
+        assert_eq!(history.check(1, "2", at(15)), Err(GuessRejection::RateLimited(45)));
+        assert_eq!(GuessHistory { path: PathBuf::new(), guesses: Vec::new() }.remaining_cooldown(at(0)), None);
+    }
+
+    #[test]
+    fn load_skips_invalid_lines() {
+        let dir = TestDir::new("guesses");
+        let path = dir.join("day1.guesses");
+        std::fs::write(&path, "2025-12-01T05:00:00Z 1 too_low 60 10\ngarbage\n\n2025-12-01T05:02:00Z 1 correct 0 the answer\n").unwrap();
+
+        let history = GuessHistory::load(&path);
+        let answers = history.guesses().iter().map(|guess| guess.answer.as_str()).collect::<Vec<_>>();
+        assert_eq!(answers, ["10", "the answer"]);
+    }
+
+    #[test]
+    fn record_appends_lines_that_load_again() {
+        let dir = TestDir::new("guesses");
+        let path = dir.join("day1.guesses");
+        let mut history = GuessHistory::load(&path);
+        history.record(Guess { timestamp: at(0), part: 2, outcome: SubmitOutcome::Wait(30), cooldown: 30, answer: "7".to_string() });
+
+        let loaded = GuessHistory::load(&path);
+        assert_eq!(loaded.guesses().len(), 1);
+        assert_eq!(loaded.guesses()[0].outcome, SubmitOutcome::Wait(30));
+        assert_eq!(loaded.guesses()[0].timestamp, at(0));
+    }
+
+    /// Answers a single request with `body` and returns the raw request.
+    fn stub_server(body: String) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+
+        (endpoint, handle)
+    }
+
+    #[test]
+    fn submit_answer_against_stub_server() {
+        let dir = TestDir::new("submit");
+        std::fs::create_dir(dir.join("2025")).unwrap();
+        std::fs::write(dir.join("2025/day3.test"), "@test\ninput\n").unwrap();
+        let (endpoint, server) = stub_server(page("That's the right answer!  Please wait one minute."));
+        let config = || SubmitConfig::from_vars(|name| match name {
+            "AOC_ENDPOINT" => Some(endpoint.clone()),
+            "AOC_SESSION" => Some("secret".to_string()),
+            _ => None,
+        });
+
+        submit_answer_in(dir.path(), config, 2025, 3, 2, "1234");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2025/day/3/answer HTTP/1.1\r\n"), "{}", request);
+        assert!(request.contains("Cookie: session=secret\r\n"), "{}", request);
+        assert!(request.ends_with("level=2&answer=1234"), "{}", request);
+
+        let history = GuessHistory::load(&dir.join("2025/day3.guesses"));
+        assert_eq!(history.guesses().len(), 1);
+        assert_eq!((history.guesses()[0].outcome, history.guesses()[0].cooldown), (SubmitOutcome::Correct, 60));
+        assert_eq!(std::fs::read_to_string(dir.join("2025/day3.test")).unwrap(), "@test\n@part2 1234\ninput\n");
+    }
+
+    #[test]
+    fn submit_answer_skips_the_server_for_known_wrong_answers() {
+        let dir = TestDir::new("submit");
+        std::fs::create_dir(dir.join("2024")).unwrap();
+        std::fs::write(dir.join("2024/day3.guesses"), "2024-12-03T05:00:00Z 1 too_high 0 100\n").unwrap();
+
+        submit_answer_in(dir.path(), || panic!("The config must not be loaded"), 2024, 3, 1, "200");
+
+        assert_eq!(GuessHistory::load(&dir.join("2024/day3.guesses")).guesses().len(), 1);
+    }
+}
@@ -1,12 +1,28 @@
+use std::cell::Cell;
 use std::fmt::Display;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
 use std::time::{Duration, Instant};
 use colored::Colorize;
 use crate::utils::aoc::format_elapsed;
+use crate::utils::alloc_counter::{measure, AllocStats};
 
 pub static PRINT_RESULTS: AtomicBool = AtomicBool::new(true);
 
+/// The directives `TestSet::from` understands, every other line is part of a test input.
+const DIRECTIVES: [&str; 5] = ["@test", "@part1", "@part2", "@label1", "@label2"];
+
+thread_local! {
+    static PART1_FINISHED: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Marks the point where a solution has finished computing part 1, which splits the
+/// execution time of the current test into per-part timings. Solutions that compute both
+/// parts in the same pass don't call it and have no per-part timings.
+pub fn mark_part1_finished() {
+    PART1_FINISHED.with(|mark| mark.set(Some(Instant::now())));
+}
+
 pub struct Test {
     name: String,
     input_text: String,
@@ -53,9 +69,16 @@ impl Test {
 }
 
 pub struct TestRunResult {
+    pub test_name: String,
     pub elapsed: Duration,
+    pub part1_elapsed: Option<Duration>,
+    pub part2_elapsed: Option<Duration>,
+    pub part1_result: String,
+    pub part2_result: String,
     pub part1_success: Option<bool>,
     pub part2_success: Option<bool>,
+    /// Allocations made by the solution, only available with the `alloc-stats` feature.
+    pub alloc_stats: Option<AllocStats>,
 }
 
 pub struct TestSet {
@@ -128,6 +151,46 @@ impl TestSet {
         Self { tests }
     }
 
+    /// Returns a copy of `raw_input` where the `@part1`/`@part2` directive of the test at `index`
+    /// is set to `answer`. An existing directive is replaced, otherwise a new one is inserted
+    /// after the directives following the `@test` line. All other lines are left untouched.
+    pub fn with_answer(raw_input: &str, index: usize, part: u8, answer: &str) -> String {
+        let directive = format!("@part{}", part);
+        let mut lines = raw_input.lines().map(|line| line.to_string()).collect::<Vec<_>>();
+
+        let test_line = lines.iter()
+            .enumerate()
+            .filter(|(_, line)| line.trim().to_lowercase().starts_with("@test"))
+            .map(|(i, _)| i)
+            .nth(index)
+            .unwrap_or_else(|| panic!("Test {} does not exist", index + 1));
+
+        let mut directive_line = None;
+        let mut last_directive_line = test_line;
+        for (i, line) in lines.iter().enumerate().skip(test_line + 1) {
+            let line = line.trim().to_lowercase();
+            if !DIRECTIVES.iter().any(|name| line.starts_with(name)) || line.starts_with("@test") {
+                break;
+            }
+            last_directive_line = i;
+            if line.starts_with(&directive) {
+                directive_line = Some(i);
+            }
+        }
+
+        let new_line = format!("{} {}", directive, answer);
+        match directive_line {
+            Some(i) => lines[i] = new_line,
+            None => lines.insert(last_directive_line + 1, new_line),
+        }
+
+        let mut output = lines.join("\n");
+        if raw_input.ends_with('\n') {
+            output.push('\n');
+        }
+        output
+    }
+
     pub fn get_test(&self, index: usize) -> &Test {
         &self.tests[index]
     }
@@ -136,6 +199,10 @@ impl TestSet {
         self.tests.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+
     pub fn test_all<F, R>(&self, f: F) -> bool
     where
         F: Fn(String) -> (R, R),
@@ -163,24 +230,115 @@ impl TestSet {
         R: Display,
     {
         let test = self.get_test(index);
+        let input = test.get_input();
+        PART1_FINISHED.with(|mark| mark.set(None));
         let start_time = Instant::now();
-        let (part1, part2) = f(test.get_input());
+        let ((part1, part2), alloc_stats) = measure(|| f(input));
         let elapsed = start_time.elapsed();
+        let part1_finished = PART1_FINISHED.with(|mark| mark.take());
 
         if PRINT_RESULTS.load(Relaxed) {
             println!();
             println!("{} Results:", test.name.bold());
         }
-        let test1_result = test.check_result_1(part1.to_string().as_str());
-        let test2_result = test.check_result_2(part2.to_string().as_str());
+        let part1 = part1.to_string();
+        let part2 = part2.to_string();
+        let test1_result = test.check_result_1(part1.as_str());
+        let test2_result = test.check_result_2(part2.as_str());
         if PRINT_RESULTS.load(Relaxed) {
             println!("{}: {}", "Elapsed time".bold(), format_elapsed(elapsed, true).purple());
+            if let Some(alloc_stats) = &alloc_stats {
+                println!("{}: {}", "Allocations".bold(), alloc_stats.format());
+            }
         }
 
         TestRunResult {
+            test_name: test.name.clone(),
+            part1_elapsed: part1_finished.map(|finished| finished - start_time),
+            part2_elapsed: part1_finished.map(|finished| start_time + elapsed - finished),
+            part1_result: part1,
+            part2_result: part2,
             part1_success: test1_result,
             part2_success: test2_result,
+            alloc_stats,
             elapsed,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "@label1 Sum of ids\n@test\n@part1 12\n@part2 34\n1,2\n3\n\n@test\n@part2 7\n\n5\n";
+
+    #[test]
+    fn parses_tests_and_labels() {
+        let test_set = TestSet::from(INPUT);
+        assert_eq!(test_set.len(), 2);
+
+        let first = test_set.get_test(0);
+        assert_eq!((first.name.as_str(), first.get_input().as_str()), ("Test 1", "1,2\n3"));
+        assert_eq!((first.output_text_1.as_deref(), first.output_text_2.as_deref()), (Some("12"), Some("34")));
+        assert_eq!((first.part1_name.as_str(), first.part2_name.as_str()), ("Sum of ids", "Part 2"));
+
+        let second = test_set.get_test(1);
+        assert_eq!((second.get_input().as_str(), second.output_text_1.as_deref()), ("5", None));
+        assert_eq!(second.check_result_2("7"), Some(true));
+        assert_eq!(second.check_result_2("8"), Some(false));
+        assert_eq!(second.check_result_1("5"), None);
+        assert!(TestSet::from("").is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid data on line 1. Possible missing @test directive.")]
+    fn answer_without_test() {
+        TestSet::from("@part1 5\n@test\n1");
+    }
+
+    #[test]
+    fn with_answer_replaces_or_inserts_the_directive() {
+        let replaced = TestSet::with_answer(INPUT, 0, 1, "99");
+        assert_eq!(replaced, INPUT.replace("@part1 12", "@part1 99"));
+
+        let inserted = TestSet::with_answer(INPUT, 1, 1, "6");
+        assert_eq!(inserted, INPUT.replace("@part2 7\n", "@part2 7\n@part1 6\n"));
+        assert_eq!(TestSet::from(&inserted).get_test(1).get_input(), "5");
+
+        assert_eq!(TestSet::with_answer("@test\n1", 0, 2, "3"), "@test\n@part2 3\n1");
+    }
+
+    #[test]
+    fn with_answer_keeps_grid_inputs_of_at_signs() {
+        let input = "@test\n@part1 13\n@@.@\n.@@.\n";
+        assert_eq!(TestSet::with_answer(input, 0, 2, "43"), "@test\n@part1 13\n@part2 43\n@@.@\n.@@.\n");
+        assert_eq!(TestSet::with_answer("@test\n@@.@\n", 0, 1, "1"), "@test\n@part1 1\n@@.@\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "Test 3 does not exist")]
+    fn with_answer_for_a_missing_test() {
+        TestSet::with_answer(INPUT, 2, 1, "1");
+    }
+
+    #[test]
+    fn part_timings_need_the_part1_mark() {
+        let test_set = TestSet::from(INPUT);
+
+        let result = test_set.test_one(0, |_| (12, 34));
+        assert_eq!((result.part1_success, result.part2_success), (Some(true), Some(true)));
+        assert_eq!((result.part1_elapsed, result.part2_elapsed), (None, None));
+
+        let result = test_set.test_one(0, |_| {
+            std::thread::sleep(Duration::from_millis(5));
+            mark_part1_finished();
+            (12, 35)
+        });
+        assert_eq!((result.part1_result.as_str(), result.part2_success), ("12", Some(false)));
+        let (part1, part2) = (result.part1_elapsed.unwrap(), result.part2_elapsed.unwrap());
+        assert!(part1 >= Duration::from_millis(5));
+        assert_eq!(part1 + part2, result.elapsed);
+
+        let result = test_set.test_one(0, |_| (12, 34));
+        assert_eq!(result.part1_elapsed, None);
+    }
+}
@@ -0,0 +1,194 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use crate::utils::matrix::Matrix;
+use crate::utils::position::Position;
+
+/// A signed 2D vector for coordinate math that may leave the grid, with y growing southwards
+/// like `Position`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Vec2) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The number of king moves between the vectors.
+    pub fn chebyshev(&self, other: &Vec2) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The squared distance, which fits in a `u128` unless the coordinate differences come close
+    /// to the whole `i64` range, in which case it panics.
+    pub fn euclidean_squared(&self, other: &Vec2) -> u128 {
+        let (dx, dy) = (self.x.abs_diff(other.x) as u128, self.y.abs_diff(other.y) as u128);
+        (dx * dx).checked_add(dy * dy).expect("Squared distance overflows u128")
+    }
+
+    /// Rotates a quarter turn counter-clockwise as seen on screen, east becomes north.
+    pub fn rotate_left(&self) -> Vec2 {
+        Vec2::new(self.y, -self.x)
+    }
+
+    /// Rotates a quarter turn clockwise as seen on screen, east becomes south.
+    pub fn rotate_right(&self) -> Vec2 {
+        Vec2::new(-self.y, self.x)
+    }
+
+    /// The position of the vector if it lies inside the matrix.
+    pub fn position_in<T>(&self, matrix: &Matrix<T>) -> Option<Position> {
+        Position::try_from(*self).ok().filter(|position| matrix.contains(position))
+    }
+}
+
+/// Fails for positions with a coordinate above `i64::MAX`.
+impl TryFrom<Position> for Vec2 {
+    type Error = Position;
+
+    fn try_from(position: Position) -> Result<Self, Self::Error> {
+        match (i64::try_from(position.x), i64::try_from(position.y)) {
+            (Ok(x), Ok(y)) => Ok(Vec2::new(x, y)),
+            _ => Err(position),
+        }
+    }
+}
+
+impl From<(i64, i64)> for Vec2 {
+    fn from((x, y): (i64, i64)) -> Self {
+        Vec2::new(x, y)
+    }
+}
+
+impl From<Vec2> for (i64, i64) {
+    fn from(vec: Vec2) -> Self {
+        (vec.x, vec.y)
+    }
+}
+
+/// Fails for vectors with a negative coordinate.
+impl TryFrom<Vec2> for Position {
+    type Error = Vec2;
+
+    fn try_from(vec: Vec2) -> Result<Self, Self::Error> {
+        match (usize::try_from(vec.x), usize::try_from(vec.y)) {
+            (Ok(x), Ok(y)) => Ok(Position::new(x, y)),
+            _ => Err(vec),
+        }
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Self::Output {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Self::Output {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, factor: i64) -> Self::Output {
+        Vec2::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Self::Output {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Vec2::new(3, -2);
+        let b = Vec2::new(-1, 5);
+        assert_eq!(a + b, Vec2::new(2, 3));
+        assert_eq!(a - b, Vec2::new(4, -7));
+        assert_eq!(a * -3, Vec2::new(-9, 6));
+        assert_eq!(-a, Vec2::new(-3, 2));
+
+        let mut c = a;
+        c += b;
+        c -= Vec2::new(2, 3);
+        assert_eq!(c, Vec2::ZERO);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Vec2::new(-2, 1);
+        let b = Vec2::new(1, -3);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean_squared(&b), 25);
+        assert_eq!(Vec2::new(0, 0).euclidean_squared(&Vec2::new(5_000_000_000, 0)), 25_000_000_000_000_000_000);
+        assert_eq!(Vec2::new(i64::MIN, 0).euclidean_squared(&Vec2::new(i64::MAX, 0)), u64::MAX as u128 * u64::MAX as u128);
+        assert_eq!(b.manhattan(&a), 7);
+        assert_eq!(a.manhattan(&a), 0);
+        assert_eq!(Vec2::new(i64::MIN, 0).manhattan(&Vec2::new(i64::MAX, 0)), u64::MAX);
+    }
+
+    #[test]
+    fn rotations_follow_the_screen() {
+        let east = Vec2::new(1, 0);
+        assert_eq!(east.rotate_left(), Vec2::new(0, -1));
+        assert_eq!(east.rotate_right(), Vec2::new(0, 1));
+        assert_eq!(Vec2::new(2, 3).rotate_left().rotate_right(), Vec2::new(2, 3));
+        assert_eq!(Vec2::new(2, 3).rotate_right().rotate_right(), Vec2::new(-2, -3));
+    }
+
+    #[test]
+    fn converts_to_and_from_positions() {
+        assert_eq!(Vec2::try_from(Position::new(4, 7)), Ok(Vec2::new(4, 7)));
+        assert_eq!(Vec2::try_from(Position::new(i64::MAX as usize, 0)), Ok(Vec2::new(i64::MAX, 0)));
+        assert_eq!(Vec2::try_from(Position::new(0, i64::MAX as usize + 1)), Err(Position::new(0, i64::MAX as usize + 1)));
+        assert_eq!(Position::try_from(Vec2::new(4, 7)), Ok(Position::new(4, 7)));
+        assert_eq!(Position::try_from(Vec2::new(-1, 7)), Err(Vec2::new(-1, 7)));
+        assert_eq!(Position::try_from(Vec2::new(4, -7)), Err(Vec2::new(4, -7)));
+        assert_eq!(Vec2::from((4, -7)), Vec2::new(4, -7));
+        assert_eq!(<(i64, i64)>::from(Vec2::new(4, -7)), (4, -7));
+    }
+
+    #[test]
+    fn position_in_checks_the_matrix_bounds() {
+        let matrix = Matrix::<u8>::new(3, 2);
+        assert_eq!(Vec2::new(2, 1).position_in(&matrix), Some(Position::new(2, 1)));
+        assert_eq!(Vec2::new(3, 1).position_in(&matrix), None);
+        assert_eq!(Vec2::new(0, 2).position_in(&matrix), None);
+        assert_eq!(Vec2::new(-1, 0).position_in(&matrix), None);
+    }
+}
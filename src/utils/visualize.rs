@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::{Duration, Instant};
+use colored::Colorize;
+use terminal_size::{Height, Width};
+use crate::utils::matrix::Matrix;
+use crate::utils::position::Position;
+
+pub type Rgb = [u8; 3];
+
+const GIF_FILE_NAME: &str = "animation.gif";
+const MAX_GIF_CODE: u16 = 4096;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static VISUALIZER: Mutex<Option<Visualizer>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+pub enum VisualizeMode {
+    /// Plays the frames in the terminal.
+    Terminal,
+    /// Writes every frame as `frame_NNNNN.ppm` and all of them as `animation.gif` into the folder.
+    Record(PathBuf),
+}
+
+/// A frame of a simulation as one color per cell.
+struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+struct Visualizer {
+    mode: VisualizeMode,
+    frame_time: Duration,
+    /// Size in pixels of a single cell in recorded images.
+    scale: usize,
+    last_frame: Option<Instant>,
+    frame_count: usize,
+    frames: Vec<Frame>,
+}
+
+/// Turns on visualization for the rest of the run. Until this is called, `frame` returns
+/// right away without looking at the matrix.
+pub fn enable(mode: VisualizeMode, fps: u32, scale: usize) {
+    if let VisualizeMode::Record(folder) = &mode && let Err(error) = std::fs::create_dir_all(folder) {
+        println!("{} {}: {}", "Could not create the recording folder".red(), folder.display(), error);
+        return;
+    }
+
+    *VISUALIZER.lock().unwrap() = Some(Visualizer {
+        mode,
+        frame_time: Duration::from_secs_f64(1.0 / fps.max(1) as f64),
+        scale: scale.max(1),
+        last_frame: None,
+        frame_count: 0,
+        frames: Vec::new(),
+    });
+    ENABLED.store(true, Relaxed);
+}
+
+#[inline]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Relaxed)
+}
+
+/// Adds a frame of a simulation, `color` maps every cell to the color it is drawn in. Does
+/// nothing unless visualization was enabled, so solutions can call this on every step.
+#[inline]
+pub fn frame<T>(matrix: &Matrix<T>, color: impl Fn(&T) -> Rgb) {
+    if is_enabled() {
+        let center = Position::new(matrix.width() / 2, matrix.height() / 2);
+        push_frame(matrix, center, color);
+    }
+}
+
+/// Like `frame`, but keeps `focus` visible when the matrix doesn't fit in the terminal.
+#[inline]
+pub fn frame_at<T>(matrix: &Matrix<T>, focus: Position, color: impl Fn(&T) -> Rgb) {
+    if is_enabled() {
+        push_frame(matrix, focus, color);
+    }
+}
+
+fn push_frame<T>(matrix: &Matrix<T>, focus: Position, color: impl Fn(&T) -> Rgb) {
+    let frame = Frame {
+        width: matrix.width(),
+        height: matrix.height(),
+        pixels: matrix.as_slice().iter().map(color).collect(),
+    };
+
+    let mut visualizer = VISUALIZER.lock().unwrap();
+    let Some(visualizer) = visualizer.as_mut() else {
+        return;
+    };
+    visualizer.push(frame, focus);
+}
+
+/// Stops visualization, writing the animated GIF when recording.
+pub fn finish() {
+    ENABLED.store(false, Relaxed);
+    let Some(visualizer) = VISUALIZER.lock().unwrap().take() else {
+        return;
+    };
+
+    let VisualizeMode::Record(folder) = &visualizer.mode else {
+        return;
+    };
+    if visualizer.frames.is_empty() {
+        println!("{}", "No frames were recorded".yellow());
+        return;
+    }
+
+    let path = folder.join(GIF_FILE_NAME);
+    match write_gif(&path, &visualizer.frames, visualizer.scale, visualizer.frame_time) {
+        Ok(()) => println!("Recorded {} frames to {}", visualizer.frame_count, path.display()),
+        Err(error) => println!("{} {}: {}", "Could not write".red(), path.display(), error),
+    }
+}
+
+impl Visualizer {
+    fn push(&mut self, frame: Frame, focus: Position) {
+        match &self.mode {
+            VisualizeMode::Terminal => {
+                if let Some(last_frame) = self.last_frame {
+                    std::thread::sleep(self.frame_time.saturating_sub(last_frame.elapsed()));
+                }
+                show_in_terminal(&frame, focus, self.frame_count == 0);
+                self.last_frame = Some(Instant::now());
+            }
+            VisualizeMode::Record(folder) => {
+                let path = folder.join(format!("frame_{:05}.ppm", self.frame_count));
+                if let Err(error) = std::fs::write(&path, ppm(&frame, self.scale)) {
+                    println!("{} {}: {}", "Could not write".red(), path.display(), error);
+                }
+                self.frames.push(frame);
+            }
+        }
+        self.frame_count += 1;
+    }
+}
+
+/// Draws the frame with two block characters per cell, cropped to the terminal around `focus`.
+fn show_in_terminal(frame: &Frame, focus: Position, first: bool) {
+    let (columns, rows) = terminal_size::terminal_size()
+        .map(|(Width(width), Height(height))| (width as usize / 2, (height as usize).saturating_sub(1)))
+        .unwrap_or((40, 24));
+
+    let window = |focus: usize, size: usize, total: usize| {
+        let start = focus.saturating_sub(size / 2).min(total.saturating_sub(size));
+        start..(start + size).min(total)
+    };
+
+    let mut output = String::from(if first { "\x1b[2J\x1b[H" } else { "\x1b[H" });
+    for y in window(focus.y, rows, frame.height) {
+        for x in window(focus.x, columns, frame.width) {
+            let [r, g, b] = frame.pixels[y * frame.width + x];
+            output.push_str(&"██".truecolor(r, g, b).to_string());
+        }
+        output.push_str("\x1b[K\n");
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    _ = stdout.write_all(output.as_bytes());
+    _ = stdout.flush();
+}
+
+/// Encodes the frame as a binary PPM image with `scale` x `scale` pixels per cell.
+fn ppm(frame: &Frame, scale: usize) -> Vec<u8> {
+    let mut image = format!("P6\n{} {}\n255\n", frame.width * scale, frame.height * scale).into_bytes();
+    for y in 0..frame.height * scale {
+        for x in 0..frame.width * scale {
+            image.extend_from_slice(&frame.pixels[(y / scale) * frame.width + x / scale]);
+        }
+    }
+
+    image
+}
+
+/// Writes the frames as a looping GIF. Colors are reduced to 3-3-2 bits per channel when the
+/// frames use more than the 256 colors a GIF palette can hold.
+fn write_gif(path: &Path, frames: &[Frame], scale: usize, frame_time: Duration) -> std::io::Result<()> {
+    let screen_width = frames.iter().map(|frame| frame.width * scale).max().unwrap_or(0);
+    let screen_height = frames.iter().map(|frame| frame.height * scale).max().unwrap_or(0);
+    if screen_width > u16::MAX as usize || screen_height > u16::MAX as usize {
+        return Err(std::io::Error::other(format!("{}x{} is too large for a GIF", screen_width, screen_height)));
+    }
+
+    let mut palette: HashMap<Rgb, u8> = HashMap::new();
+    let mut colors = frames.iter().flat_map(|frame| frame.pixels.iter().copied()).collect::<Vec<_>>();
+    colors.sort_unstable();
+    colors.dedup();
+    let quantize = colors.len() > 256;
+    let color_table = if quantize {
+        (0..=255u8).map(|index| [(index >> 5) * 36, ((index >> 2) & 7) * 36, (index & 3) * 85]).collect::<Vec<_>>()
+    } else {
+        colors
+    };
+    for (index, color) in color_table.iter().enumerate() {
+        palette.insert(*color, index as u8);
+    }
+    let index_of = |[r, g, b]: Rgb| -> u8 {
+        if quantize { (r >> 5) << 5 | (g >> 5) << 2 | b >> 6 } else { palette[&[r, g, b]] }
+    };
+
+    let mut gif = Vec::new();
+    gif.extend_from_slice(b"GIF89a");
+    gif.extend_from_slice(&(screen_width as u16).to_le_bytes());
+    gif.extend_from_slice(&(screen_height as u16).to_le_bytes());
+    gif.extend_from_slice(&[0xF7, 0, 0]);
+    for index in 0..256 {
+        gif.extend_from_slice(&color_table.get(index).copied().unwrap_or_default());
+    }
+    // Netscape extension to loop forever.
+    gif.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+    gif.extend_from_slice(b"NETSCAPE2.0");
+    gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    let delay = (frame_time.as_millis() / 10).clamp(1, u16::MAX as u128) as u16;
+    for frame in frames {
+        let (width, height) = (frame.width * scale, frame.height * scale);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| index_of(frame.pixels[(y / scale) * frame.width + x / scale]))
+            .collect::<Vec<_>>();
+
+        gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0x00, 0x00]);
+
+        gif.push(0x2C);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&(width as u16).to_le_bytes());
+        gif.extend_from_slice(&(height as u16).to_le_bytes());
+        gif.push(0);
+
+        gif.push(8);
+        for block in lzw_encode(&pixels).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0);
+    }
+    gif.push(0x3B);
+
+    std::fs::write(path, gif)
+}
+
+/// GIF flavoured LZW with a minimum code size of 8 bits, codes grow up to 12 bits and the table
+/// is cleared when it is full.
+fn lzw_encode(pixels: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END: u16 = 257;
+
+    let mut output = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut emit = |code: u16, size: u32, output: &mut Vec<u8>| {
+        buffer |= (code as u32) << bits;
+        bits += size;
+        while bits >= 8 {
+            output.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    };
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = END + 1;
+    let mut code_size = 9;
+    emit(CLEAR, code_size, &mut output);
+
+    let Some((&first, rest)) = pixels.split_first() else {
+        emit(END, code_size, &mut output);
+        emit(0, 7, &mut output);
+        return output;
+    };
+
+    let mut current = first as u16;
+    for &pixel in rest {
+        if let Some(&code) = table.get(&(current, pixel)) {
+            current = code;
+            continue;
+        }
+
+        emit(current, code_size, &mut output);
+        if next_code == MAX_GIF_CODE {
+            emit(CLEAR, code_size, &mut output);
+            table.clear();
+            next_code = END + 1;
+            code_size = 9;
+        } else {
+            if next_code >= 1 << code_size {
+                code_size += 1;
+            }
+            table.insert((current, pixel), next_code);
+            next_code += 1;
+        }
+        current = pixel as u16;
+    }
+
+    emit(current, code_size, &mut output);
+    // Decoders add a table entry for the last code as well, which can widen the end code.
+    if next_code == 1 << code_size && code_size < 12 {
+        code_size += 1;
+    }
+    emit(END, code_size, &mut output);
+    // Flush the remaining bits.
+    emit(0, 7, &mut output);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_dir::TestDir;
+    use super::*;
+
+    /// A strict GIF LZW decoder with a minimum code size of 8 bits, which fails when the codes
+    /// run past the end of the data or leave more than the final partial byte unread.
+    fn lzw_decode(data: &[u8]) -> Vec<u8> {
+        let mut position = 0;
+        let mut read = |size: usize| -> u16 {
+            assert!(position + size <= data.len() * 8, "Code at bit {} runs past the end of the data", position);
+            let code = (0..size).map(|bit| (((data[(position + bit) / 8] >> ((position + bit) % 8)) & 1) as u16) << bit).sum();
+            position += size;
+            code
+        };
+
+        let initial_table = || (0..=257u16).map(|code| if code < 256 { vec![code as u8] } else { Vec::new() }).collect::<Vec<_>>();
+        let mut table = initial_table();
+        let mut code_size = 9;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = Vec::new();
+        loop {
+            let code = read(code_size) as usize;
+            if code == 256 {
+                (table, code_size, previous) = (initial_table(), 9, None);
+                continue;
+            }
+            if code == 257 {
+                break;
+            }
+
+            let entry = match &previous {
+                None => table[code].clone(),
+                Some(previous) => {
+                    let entry = if code < table.len() {
+                        table[code].clone()
+                    } else {
+                        assert_eq!(code, table.len(), "Code {} is not in the table yet", code);
+                        [previous.as_slice(), &previous[..1]].concat()
+                    };
+                    if table.len() < MAX_GIF_CODE as usize {
+                        table.push([previous.as_slice(), &entry[..1]].concat());
+                        if table.len() == 1 << code_size && code_size < 12 {
+                            code_size += 1;
+                        }
+                    }
+                    entry
+                }
+            };
+            output.extend_from_slice(&entry);
+            previous = Some(entry);
+        }
+
+        assert!(data.len() * 8 - position < 8, "{} bits left after the end code", data.len() * 8 - position);
+        output
+    }
+
+    fn round_trip(pixels: &[u8]) {
+        assert_eq!(lzw_decode(&lzw_encode(pixels)), pixels);
+    }
+
+    #[test]
+    fn lzw_round_trips() {
+        round_trip(&[]);
+        round_trip(&[7]);
+        round_trip(&[1, 1, 1, 1, 1, 1, 1, 1, 2, 1, 1, 2]);
+        round_trip(&(0..=255).collect::<Vec<_>>());
+    }
+
+    fn noise(length: usize, values: u32) -> Vec<u8> {
+        let mut state = 12345u32;
+        (0..length)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((state >> 16) % values) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lzw_widens_the_end_code() {
+        // 255 distinct pairs make the decoder widen the codes to 10 bits right before the end code.
+        round_trip(&(0..255).collect::<Vec<_>>());
+        for length in 250..270 {
+            round_trip(&(0..length).map(|index| (index % 256) as u8).collect::<Vec<_>>());
+        }
+
+        // Around 8669 pixels of noise the widened end code ends one bit into a new byte, which
+        // is lost when it is written with the old code size.
+        let noise = noise(8700, 256);
+        for length in 8600..=8700 {
+            round_trip(&noise[..length]);
+        }
+    }
+
+    #[test]
+    fn lzw_clears_the_full_table() {
+        round_trip(&noise(50_000, 5));
+        round_trip(&noise(50_000, 256));
+        round_trip(&vec![3; 100_000]);
+        round_trip(&(0..20_000).map(|index| (index * 7 % 256) as u8).collect::<Vec<_>>());
+    }
+
+    fn frames() -> Vec<Frame> {
+        vec![
+            Frame { width: 2, height: 1, pixels: vec![[255, 0, 0], [0, 0, 255]] },
+            Frame { width: 2, height: 1, pixels: vec![[0, 0, 255], [0, 0, 255]] },
+        ]
+    }
+
+    #[test]
+    fn scales_ppm_images() {
+        let image = ppm(&frames()[0], 2);
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image[header.len()..].chunks(3).collect::<Vec<_>>(),
+                   [[255, 0, 0], [255, 0, 0], [0, 0, 255], [0, 0, 255], [255, 0, 0], [255, 0, 0], [0, 0, 255], [0, 0, 255]]);
+    }
+
+    #[test]
+    fn writes_a_decodable_gif() {
+        let dir = TestDir::new("gif");
+        let path = dir.join(GIF_FILE_NAME);
+        write_gif(&path, &frames(), 2, Duration::from_millis(100)).unwrap();
+        let gif = std::fs::read(&path).unwrap();
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!((u16::from_le_bytes([gif[6], gif[7]]), u16::from_le_bytes([gif[8], gif[9]])), (4, 2));
+        let color_table = gif[13..13 + 768].chunks(3).collect::<Vec<_>>();
+        assert_eq!(*gif.last().unwrap(), 0x3B);
+
+        let mut images = Vec::new();
+        let mut offset = 13 + 768;
+        while gif[offset] != 0x3B {
+            let sub_blocks_start = match gif[offset] {
+                0x21 => offset + 2,
+                0x2C => {
+                    assert_eq!(gif[offset + 10], 8);
+                    offset + 11
+                }
+                block => panic!("Unexpected block 0x{:02X} at {}", block, offset),
+            };
+
+            let mut data = Vec::new();
+            offset = sub_blocks_start;
+            while gif[offset] != 0 {
+                let length = gif[offset] as usize;
+                data.extend_from_slice(&gif[offset + 1..offset + 1 + length]);
+                offset += length + 1;
+            }
+            offset += 1;
+            if gif[sub_blocks_start - 11] == 0x2C {
+                images.push(lzw_decode(&data).iter().map(|index| color_table[*index as usize].to_vec()).collect::<Vec<_>>());
+            }
+        }
+
+        let (red, blue) = (vec![255, 0, 0], vec![0, 0, 255]);
+        assert_eq!(images, [
+            vec![red.clone(), red.clone(), blue.clone(), blue.clone(), red.clone(), red, blue.clone(), blue.clone()],
+            vec![blue; 8],
+        ]);
+    }
+
+    #[test]
+    fn quantizes_large_palettes() {
+        let pixels = (0..300).map(|index| [index as u8, (index / 256) as u8 * 200, 0]).collect::<Vec<_>>();
+        let frames = [Frame { width: 300, height: 1, pixels }];
+        let dir = TestDir::new("gif-quantized");
+        write_gif(&dir.join(GIF_FILE_NAME), &frames, 1, Duration::from_millis(100)).unwrap();
+
+        let gif = std::fs::read(dir.join(GIF_FILE_NAME)).unwrap();
+        assert_eq!(&gif[13 + 3 * 0b111_000_01..13 + 3 * 0b111_000_01 + 3], [252, 0, 85]);
+    }
+}